@@ -1,5 +1,38 @@
 #![no_std]
-use soroban_sdk::{contract, contracttype, contractimpl, Address, Env, Symbol, token};
+use soroban_sdk::{contract, contracterror, contracttype, contractimpl, Address, Bytes, BytesN, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec, token};
+
+// --- CONSTANTS ---
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const BPS_DENOMINATOR: i128 = 10_000;
+// Upper bound on any basis-point setting, i.e. 100%
+const MAX_BPS: u32 = 10_000;
+// Upper bound on the total escalating penalty (rate * days), i.e. 100% of the contribution
+const MAX_PENALTY_BPS: u32 = 10_000;
+// Bounds enforced by create_circle
+const MIN_MEMBERS: u32 = 2;
+const MAX_MEMBERS: u32 = 100;
+const MIN_CYCLE_DURATION: u64 = SECONDS_PER_DAY;
+const MAX_CYCLE_DURATION: u64 = 365 * SECONDS_PER_DAY;
+// Co-organizers the creator may appoint per circle
+const MAX_CO_ORGANIZERS: u32 = 5;
+// Time the proposed admin has to accept a transfer
+const ADMIN_TRANSFER_WINDOW: u64 = 7 * SECONDS_PER_DAY;
+// Time a protocol action proposal stays open for approvals
+const PROPOSAL_WINDOW: u64 = 7 * SECONDS_PER_DAY;
+// Per-circle and per-member entries are extended to TTL_EXTEND_TO whenever they are accessed
+// with less than TTL_THRESHOLD left (ledgers close roughly every 5 seconds)
+const DAY_IN_LEDGERS: u32 = 17_280;
+const TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+// Members bump_members extends per call; each touches about ten entries, which keeps a page
+// within the network's per-transaction ledger entry limit
+const MAX_BUMP_MEMBERS: u32 = 3;
+// Published as the second topic of every event; bump when an event's data layout changes
+const EVENT_VERSION: u32 = 1;
+// Storage layout version; 1 is the original layout with global Member(Address) records
+const SCHEMA_VERSION: u32 = 2;
+const LEGACY_SCHEMA_VERSION: u32 = 1;
 
 // --- DATA STRUCTURES ---
 
@@ -7,17 +40,70 @@ use soroban_sdk::{contract, contracttype, contractimpl, Address, Env, Symbol, to
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
+    // Admin transfer awaiting acceptance by the proposed admin
+    PendingAdmin,
+    // Addresses holding each protocol role
+    RoleMembers(Role),
+    // Super-admin approvals required to execute a protocol action (k of n)
+    ApprovalThreshold,
+    ProposalCount,
+    Proposal(u64),
+    // Protocol-wide pause switch and accumulated paused time
+    ProtocolPause,
+    // Storage layout version and the next legacy circle to convert during migrate
+    SchemaVersion,
+    MigrationCursor,
+    // Marks circles converted from the legacy layout, whose members migrate_members may move over
+    LegacyCircle(u64),
+    // Legacy tokens held by the contract that migrated deposits and claimables may still draw on (Token)
+    LegacyFunds(Address),
     Circle(u64),
-    Member(Address),
+    // Membership is scoped per circle (CircleID, UserAddress)
+    Member(u64, Address),
     CircleCount,
-    // New: Tracks if a user has paid for a specific circle (CircleID, UserAddress)
-    Deposit(u64, Address),
-    // New: Tracks Group Reserve balance for penalties
-    GroupReserve,
+    // Tracks if a user has paid for a specific round (CircleID, Round, UserAddress)
+    Deposit(u64, u32, Address),
+    // Tracks Group Reserve balance collected from penalties (CircleID, Token)
+    GroupReserve(u64, Address),
     // New: Tracks next cycle contribution amount for each circle
     NextCycleAmount(u64),
     // New: Tracks claimable balances for each user in each circle
     ClaimableBalance(u64, Address),
+    // Ordered list of member addresses for each circle (join order)
+    CircleMembers(u64),
+    // Addresses the creator has appointed to help manage a circle
+    CoOrganizers(u64),
+    // Payout order fixed when the rotation starts
+    PayoutOrder(u64),
+    // Commit-reveal order seeding: member commitments and the combined seed
+    SeedCommit(u64, Address),
+    OrderSeed(u64),
+    // Security deposit held for each member (CircleID, UserAddress)
+    Collateral(u64, Address),
+    // Marks a round a member defaulted on (CircleID, Round, UserAddress)
+    Defaulted(u64, u32, Address),
+    // Outstanding debt to the Group Reserve from covered defaults (CircleID, UserAddress)
+    Debt(u64, Address),
+    // Uncovered parts of defaulted contributions, owed to each round's recipient (CircleID, UserAddress)
+    Arrears(u64, Address),
+    // Savings track record aggregated across all circles
+    Reputation(Address),
+    // State of each round in a circle (CircleID, Round)
+    Round(u64, u32),
+    // Member a settled round's pot went to (CircleID, Round)
+    RoundRecipient(u64, u32),
+    // Tokens actually held for a circle: credited on deposit, debited on claim
+    CircleBalance(u64),
+    // Per-circle pause switch and the paused time that shifts its deadlines
+    CirclePause(u64),
+    // Best bid so far in an auction round (CircleID, Round)
+    Bid(u64, u32),
+    // Sealed bid commitment sha256(discount || salt) (CircleID, Round, UserAddress)
+    BidCommit(u64, u32, Address),
+    // Running total of auction discount shares owed to each member, and the point up to which a
+    // member has collected them (CircleID, UserAddress)
+    DiscountShares(u64),
+    DiscountCheckpoint(u64, Address),
 }
 
 #[contracttype]
@@ -27,6 +113,207 @@ pub struct Member {
     pub has_contributed: bool,
     pub contribution_count: u32,
     pub last_contribution_time: u64,
+    pub has_received_payout: bool,
+}
+
+// A member's standing in a circle, as returned by get_member_status
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberStatus {
+    pub member: Member,
+    pub round: u32, // The circle's current round
+    pub paid_current_round: bool, // Contributed to (or was defaulted on) the current round
+    pub penalty_due: i128, // Late penalty that would be charged if they paid now
+    pub amount_due: i128, // Contribution plus penalty still owed for the current round
+    pub debt: i128, // Outstanding debt from defaults, to the reserve and to round recipients
+    pub claimable: i128,
+    pub collateral: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reputation {
+    pub rounds_on_time: u32,
+    pub rounds_late: u32,
+    pub rounds_defaulted: u32,
+    pub circles_joined: u32,
+    pub circles_completed: u32,
+    pub debt_repaid: i128,
+    pub open_defaults: u32, // Circles in which the address still has outstanding debt
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundStatus {
+    Open,    // Accepting contributions
+    Closed,  // Every member has paid, ready for payout
+    Settled, // Pot has been assigned to the recipient
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RoundInfo {
+    pub round: u32,
+    pub status: RoundStatus,
+    pub paid_count: u32,
+    pub pot: i128, // Contributions actually received for this round
+    pub closed_at: u64, // When every member had paid; auction bid windows start here
+    pub opened_at: u64, // When the round started accepting contributions
+    pub opened_paused: u64, // Circle's paused time when the round opened
+}
+
+// Part of a defaulted contribution that neither collateral nor the reserve covered
+#[contracttype]
+#[derive(Clone)]
+pub struct Arrear {
+    pub round: u32,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BidInfo {
+    pub bidder: Address,
+    pub discount: i128, // Amount the bidder gives up, shared among the other members
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AuctionConfig {
+    pub bid_window: u64, // Seconds after the round closes to bid (sealed: to commit, then as long again to reveal)
+    pub sealed: bool, // Commit-reveal bids instead of open bids
+    pub max_discount_bps: u32, // Largest discount a bid may offer, in basis points of the pot
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PayoutMode {
+    Rotation,              // Fixed order: the pot goes to the next member in the rotation
+    Auction(AuctionConfig), // Members bid a discount to receive the pot early
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PenaltyPolicy {
+    pub rate_bps: u32, // Penalty per day late, in basis points of the contribution
+    pub flat_fee: i128, // Fixed fee added to any late payment
+    pub min_fee: i128, // Lower bound on the total penalty once it applies
+    pub max_escalation_days: u32, // Days of lateness after which the rate stops escalating
+    pub grace_period: u64, // Seconds after the deadline before any penalty applies
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircleStatus {
+    Forming,   // Accepting members until full or the fill deadline
+    Revealing, // Membership locked, waiting for every member to reveal their order seed
+    Active,    // Rotation in progress
+    Completed, // Every member has received the pot
+    Cancelled, // Did not fill in time; contributions refunded
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderMode {
+    JoinOrder,    // Payout order is join order
+    Prng,         // Shuffled with the on-chain PRNG when the circle starts
+    CommitReveal, // Shuffled with a seed every member contributes to via commit_seed/reveal_seed
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeedCommitment {
+    pub commitment: BytesN<32>, // sha256(secret)
+    pub revealed: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeedState {
+    pub seed: BytesN<32>, // XOR of every revealed secret
+    pub committed: u32,
+    pub revealed: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    SuperAdmin, // Approves protocol actions and holds every other role
+    Operator,   // Starts circles and distributes payouts for any circle
+    Pauser,     // Pauses the protocol or individual circles
+    Treasurer,  // Proposes Group Reserve withdrawals
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ProtocolAction {
+    SetNextCycleAmount(u64, i128),       // (CircleID, Amount)
+    WithdrawReserve(u64, Address, i128), // (CircleID, Recipient, Amount) in the circle's token
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    SetApprovalThreshold(u32),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Proposal {
+    pub id: u64,
+    pub action: ProtocolAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>, // Super-admins who approved
+    pub expires_at: u64,
+    pub executed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Default)]
+pub struct ProtocolPauseState {
+    pub paused: bool,
+    pub paused_at: u64,
+    pub total_paused: u64, // Seconds paused across completed pauses
+}
+
+#[contracttype]
+#[derive(Clone, Default)]
+pub struct CirclePauseState {
+    pub paused: bool,
+    pub paused_at: u64,
+    pub protocol_paused_at: u64, // Protocol paused time when this circle was paused
+    pub total_paused: u64,       // Circle-only paused seconds since the circle was created, then since the rotation started
+    pub protocol_base: u64,      // Protocol paused time when the circle was created, then when the rotation started
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expires_at: u64, // accept_admin must be called by this timestamp
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AdmissionCriteria {
+    pub min_completed_circles: u32,
+    pub max_open_defaults: u32,
+    pub max_late_ratio_bps: u32, // Late or defaulted rounds over all rounds, in basis points
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum Admission {
+    Open,                     // Anyone may join
+    Gated(AdmissionCriteria), // join_circle checks the joiner's reputation
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct CircleConfig {
+    pub min_members: u32, // Creator may start the circle early once this many have joined
+    pub fill_period: u64, // Seconds after creation the circle has to start before it can be cancelled
+    pub payout_mode: PayoutMode,
+    pub order_mode: OrderMode,
+    pub collateral_amount: i128, // Security deposit transferred on join (0 for none)
+    pub admission: Admission,
 }
 
 #[contracttype]
@@ -37,11 +324,100 @@ pub struct CircleInfo {
     pub contribution_amount: i128, // Changed back to i128 for token compatibility
     pub max_members: u32, // Changed from u16 to u32 for Soroban compatibility
     pub member_count: u32, // Changed from u16 to u32 for Soroban compatibility
-    pub current_recipient_index: u32, // Changed from u16 to u32 for Soroban compatibility
-    pub is_active: bool,
+    pub current_recipient_index: u32, // Index into the circle's member list of the next payout recipient
+    pub status: CircleStatus,
+    pub current_round: u32, // 1-based number of the round currently collecting or awaiting payout
     pub token: Address, // The token used (USDC, XLM)
-    pub deadline_timestamp: u64, // Deadline for on-time payments
+    pub start_timestamp: u64, // Schedule anchor: round N is due at start + N * cycle_duration (set when the circle starts)
+    pub fill_deadline: u64, // Circle can be cancelled if it has not started by this time
     pub cycle_duration: u64, // Duration of each payment cycle in seconds
+    pub penalty: PenaltyPolicy, // How late payments are charged
+    pub config: CircleConfig,
+}
+
+// --- LEGACY SCHEMA ---
+
+// Keys of the original layout that no longer exist in DataKey
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub enum LegacyDataKey {
+    // Membership was global rather than scoped per circle
+    Member(Address),
+    // Paid flag for the current cycle (CircleID, UserAddress)
+    Deposit(u64, Address),
+}
+
+// Legacy circles (id, creator, contribution_amount as u64 or i128, max_members, token,
+// cycle_duration, ...) are read field by field, see migrate_legacy_circle
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub struct LegacyMember {
+    pub address: Address,
+    pub has_contributed: bool,
+    pub contribution_count: u32,
+    pub last_contribution_time: u64,
+}
+
+// --- ERRORS ---
+
+// Codes are documented in the README troubleshooting table; each code has a single meaning and
+// codes are never reused. Contract specs allow at most 50 error variants and this enum is at
+// that limit: a new failure must use an existing code with the same meaning, or a code must be
+// retired to make room for it.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    CycleNotComplete = 1001,
+    AlreadyJoined = 1003,
+    CircleNotFound = 1004,
+    Unauthorized = 1005,
+    CircleFull = 1007,
+    NotMember = 1008,
+    CircleCompleted = 1009,
+    NoClaimableBalance = 1011,
+    InsufficientBalance = 1012, // The payer, the circle's pot or the reserve holds too little
+    RoundNotOpen = 1013,
+    AlreadyPaid = 1014,
+    RoundInProgress = 1015, // Membership is locked once the circle has started
+    RoundNotFound = 1016,
+    InvalidPenaltyPolicy = 1018,
+    InvalidCircleConfig = 1019,
+    CircleNotActive = 1020,
+    FillDeadlinePassed = 1021,
+    DeadlineNotReached = 1022, // Fill deadline (cancel) or grace period (mark_default) still running
+    NotEnoughMembers = 1023,
+    InvalidAmount = 1024,
+    InvalidMemberCount = 1025,
+    InvalidCycleDuration = 1026,
+    InvalidToken = 1027,
+    UnsupportedMode = 1028,
+    BiddingClosed = 1029,
+    BiddingOpen = 1030,
+    BidTooLow = 1031,
+    InvalidBid = 1032,
+    AlreadyReceived = 1033,
+    CommitmentMismatch = 1034,
+    SeedAlreadySubmitted = 1037,
+    OrderNotSet = 1038,
+    AwaitingCommitments = 1040,
+    AdmissionCriteriaNotMet = 1043,
+    AlreadyOrganizer = 1044,
+    NotOrganizer = 1045,
+    TooManyOrganizers = 1046,
+    NoPendingAdmin = 1047,
+    AdminTransferExpired = 1048,
+    AlreadyInitialized = 1049,
+    NotInitialized = 1050,
+    ProposalExecuted = 1051,
+    AlreadyApproved = 1052,
+    InvalidThreshold = 1053,
+    Paused = 1054,
+    RoleAlreadyHeld = 1055,
+    RoleNotHeld = 1056,
+    ProposalNotFound = 1057,
+    ProposalExpired = 1058,
+    InvalidLegacyData = 1059,
 }
 
 // --- EVENTS ---
@@ -53,281 +429,2580 @@ pub struct AdminChangedEvent {
     pub new_admin: Address,
 }
 
+// Every event is published with topics (name, EVENT_VERSION), plus the circle ID for circle events
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CircleCreatedEvent {
+    pub creator: Address,
+    pub token: Address,
+    pub contribution_amount: i128,
+    pub max_members: u32,
+    pub cycle_duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CircleStatusEvent {
+    pub status: CircleStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MemberJoinedEvent {
+    pub member: Address,
+    pub member_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MemberLeftEvent {
+    pub member: Address,
+    pub member_count: u32,
+    pub refund: i128, // Early contribution plus collateral returned
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DepositEvent {
+    pub member: Address,
+    pub round: u32,
+    pub amount: i128,
+    pub penalty: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DefaultEvent {
+    pub member: Address,
+    pub round: u32,
+    pub from_collateral: i128,
+    pub from_reserve: i128,
+    pub uncovered: i128, // Left out of the pot and owed to the round's recipient
+    pub debt: i128, // Member's total outstanding debt afterwards
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PayoutEvent {
+    pub recipient: Address,
+    pub round: u32,
+    pub amount: i128,   // Credited to the recipient
+    pub discount: i128, // Shared among the other members (auction circles)
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ClaimEvent {
+    pub member: Address,
+    pub amount: i128,
+    pub collateral: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct NextAmountSetEvent {
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AmountChangedEvent {
+    pub round: u32, // First round collected at the new amount
+    pub old_amount: i128,
+    pub new_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PauseEvent {
+    pub caller: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AdminTransferProposedEvent {
+    pub current_admin: Address,
+    pub new_admin: Address,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AdminTransferCancelledEvent {
+    pub current_admin: Address,
+    pub new_admin: Address,
+}
+
 // --- CONTRACT TRAIT ---
 
 pub trait SoroSusuTrait {
-    // Initialize the contract
-    fn init(env: Env, admin: Address);
+    // Initialize the contract (once). The admin must be the account that deployed this contract
+    // with `salt`, and must sign, so nobody can front-run the deployer between deploy and init
+    fn init(env: Env, admin: Address, salt: BytesN<32>) -> Result<(), Error>;
     
     // Create a new savings circle
-    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64) -> u64;
+    #[allow(clippy::too_many_arguments)]
+    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64, penalty: PenaltyPolicy, config: CircleConfig) -> Result<u64, Error>;
 
-    // Join an existing circle
-    fn join_circle(env: Env, user: Address, circle_id: u64);
+    // Start a forming circle early once it has at least min_members (Circle organizers or operators)
+    fn start_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error>;
 
-    // Make a deposit (Pay your weekly/monthly due)
-    fn deposit(env: Env, user: Address, circle_id: u64);
-    
-    // Transfer admin role to another user
-    fn transfer_admin(env: Env, current_admin: Address, new_admin: Address);
-    
-    // Set next cycle contribution amount (Admin only)
-    fn set_next_cycle_amount(env: Env, admin: Address, circle_id: u64, amount: i128);
-    
-    // Distribute funds to members (pull pattern)
-    fn distribute_funds(env: Env, admin: Address, circle_id: u64);
-    
-    // Claim funds from distribution
-    fn claim(env: Env, user: Address, circle_id: u64);
-}
+    // Appoint a co-organizer who shares the creator's authority over the circle (Creator only)
+    fn add_co_organizer(env: Env, creator: Address, circle_id: u64, co_organizer: Address) -> Result<(), Error>;
 
-// --- IMPLEMENTATION ---
+    // Revoke a co-organizer (Creator only)
+    fn remove_co_organizer(env: Env, creator: Address, circle_id: u64, co_organizer: Address) -> Result<(), Error>;
 
-#[contract]
-pub struct SoroSusu;
+    // Get the co-organizers of a circle
+    fn get_co_organizers(env: Env, circle_id: u64) -> Result<Vec<Address>, Error>;
 
-#[contractimpl]
-impl SoroSusuTrait for SoroSusu {
-    fn init(env: Env, admin: Address) {
-        // Initialize the circle counter to 0 if it doesn't exist
-        if !env.storage().instance().has(&DataKey::CircleCount) {
-            env.storage().instance().set(&DataKey::CircleCount, &0u64);
-        }
-        // Set the admin
-        env.storage().instance().set(&DataKey::Admin, &admin);
-    }
+    // Cancel a circle that did not start before its fill deadline and refund contributions (anyone)
+    fn cancel_circle(env: Env, circle_id: u64) -> Result<(), Error>;
 
-    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64) -> u64 {
-        // 1. Get the current Circle Count
-        let mut circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
-        
-        // 2. Increment the ID for the new circle
-        circle_count += 1;
+    // Commit once to a secret seed as sha256(secret) before reveals start (CommitReveal circles)
+    fn commit_seed(env: Env, user: Address, circle_id: u64, commitment: BytesN<32>) -> Result<(), Error>;
 
-        // 3. Create the Circle Data Struct
-        let current_time = env.ledger().timestamp();
-        let new_circle = CircleInfo {
-            id: circle_count,
-            creator: creator.clone(),
-            contribution_amount: amount,
-            max_members,
-            member_count: 0,
-            current_recipient_index: 0,
-            is_active: true,
-            token,
-            deadline_timestamp: current_time + cycle_duration,
-            cycle_duration,
-        };
+    // Reveal the committed secret once membership is locked and every member has committed;
+    // the last reveal shuffles the order and starts the circle
+    fn reveal_seed(env: Env, user: Address, circle_id: u64, secret: BytesN<32>) -> Result<(), Error>;
 
-        // 4. Save the Circle and the new Count
-        env.storage().instance().set(&DataKey::Circle(circle_count), &new_circle);
-        env.storage().instance().set(&DataKey::CircleCount, &circle_count);
+    // Get the payout order fixed when the circle started
+    fn get_payout_order(env: Env, circle_id: u64) -> Result<Vec<Address>, Error>;
 
-        // 5. Initialize Group Reserve if not exists
-        if !env.storage().instance().has(&DataKey::GroupReserve) {
-            env.storage().instance().set(&DataKey::GroupReserve, &0u64);
-        }
+    // Join an existing circle
+    fn join_circle(env: Env, user: Address, circle_id: u64) -> Result<(), Error>;
 
-        // 6. Return the new ID
-        circle_count
-    }
+    // Leave a circle that is still forming, refunding any early contribution and collateral
+    fn leave_circle(env: Env, user: Address, circle_id: u64) -> Result<(), Error>;
 
-    fn join_circle(env: Env, user: Address, circle_id: u64) {
-        // 1. Authorization: The user MUST sign this transaction
-        user.require_auth();
+    // Make a deposit (Pay your weekly/monthly due)
+    fn deposit(env: Env, user: Address, circle_id: u64) -> Result<(), Error>;
+    
+    // Propose transferring the admin role; takes effect once the new admin accepts (Admin only)
+    fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) -> Result<(), Error>;
 
-        // 2. Retrieve the circle data
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    // Accept a pending admin transfer before it expires (Proposed admin only)
+    fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error>;
 
-        // 3. Check if the circle is full
-        if circle.member_count >= circle.max_members {
-            panic!("Circle is full");
-        }
+    // Withdraw a pending admin transfer (Admin only)
+    fn cancel_admin_transfer(env: Env, current_admin: Address) -> Result<(), Error>;
 
-        // 4. Check if user is already a member to prevent duplicates
-        let member_key = DataKey::Member(user.clone());
-        if env.storage().instance().has(&member_key) {
-            panic!("User is already a member");
-        }
+    // Get the admin transfer awaiting acceptance
+    fn get_pending_admin(env: Env) -> Result<PendingAdmin, Error>;
 
-        // 5. Create and store the new member
-        let new_member = Member {
-            address: user.clone(),
-            has_contributed: false,
-            contribution_count: 0,
-            last_contribution_time: 0,
-        };
-        
-        // 6. Store the member and update circle count
-        env.storage().instance().set(&member_key, &new_member);
-        circle.member_count += 1;
-        
-        // 7. Save the updated circle back to storage
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
-    }
+    // Propose a sensitive protocol action; it executes once enough super-admins approve
+    // (Operator for amounts, Treasurer for reserve withdrawals, Super-admin for roles and threshold)
+    fn propose_action(env: Env, proposer: Address, action: ProtocolAction) -> Result<u64, Error>;
 
-    fn deposit(env: Env, user: Address, circle_id: u64) {
-        // 1. Authorization: The user must sign this!
-        user.require_auth();
+    // Approve a pending proposal, executing it when the threshold is reached (Super-admin only)
+    fn approve_action(env: Env, approver: Address, proposal_id: u64) -> Result<(), Error>;
 
-        // 2. Load the Circle Data
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    // Get a protocol action proposal
+    fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error>;
 
-        // 2.1. Check if there is a next cycle amount set
-        let next_cycle_amount: Option<i128> = env.storage().instance().get(&DataKey::NextCycleAmount(circle_id));
-        let contribution_amount = next_cycle_amount.unwrap_or(circle.contribution_amount);
+    // Check whether an address was granted a role
+    fn has_role(env: Env, role: Role, account: Address) -> Result<bool, Error>;
 
-        // 3. Check if user is actually a member
-        let member_key = DataKey::Member(user.clone());
-        let mut member: Member = env.storage().instance().get(&member_key)
-            .unwrap_or_else(|| panic!("User is not a member of this circle"));
+    // Get the addresses holding a role
+    fn get_role_members(env: Env, role: Role) -> Result<Vec<Address>, Error>;
 
-        // 4. Create the Token Client
-        let client = token::Client::new(&env, &circle.token);
+    // Get the number of super-admin approvals protocol actions need
+    fn get_approval_threshold(env: Env) -> Result<u32, Error>;
 
-        // 5. Check if payment is late and apply penalty if needed
-        let current_time = env.ledger().timestamp();
-        let mut penalty_amount = 0i128;
+    // Stop joins, deposits, payouts, defaults, claims and cancellation in every circle (Pauser only)
+    fn pause(env: Env, caller: Address) -> Result<(), Error>;
 
-        if current_time > circle.deadline_timestamp {
-            // Calculate 1% penalty
-            penalty_amount = contribution_amount / 100; // 1% penalty
-            
-            // Update Group Reserve balance
-            let mut reserve_balance: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-            reserve_balance += penalty_amount as u64;
-            env.storage().instance().set(&DataKey::GroupReserve, &reserve_balance);
-        }
+    // Resume the protocol; penalty deadlines move back by the time spent paused (Pauser only)
+    fn unpause(env: Env, caller: Address) -> Result<(), Error>;
 
-        // 6. Transfer the full amount from user
-        client.transfer(
-            &user, 
-            &env.current_contract_address(), 
-            &contribution_amount
-        );
+    // Stop joins, deposits, payouts, defaults, claims and cancellation in one circle (Pauser only)
+    fn pause_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error>;
 
-        // 7. Update member contribution info
-        member.has_contributed = true;
-        member.contribution_count += 1;
-        member.last_contribution_time = current_time;
-        
-        // 8. Save updated member info
-        env.storage().instance().set(&member_key, &member);
+    // Resume a paused circle (Pauser only)
+    fn unpause_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error>;
 
-        // 9. Update circle contribution amount and deadline for next cycle
-        if next_cycle_amount.is_some() {
-            circle.contribution_amount = contribution_amount;
-            // Clear the next cycle amount since it has been applied
-            env.storage().instance().remove(&DataKey::NextCycleAmount(circle_id));
-        }
-        circle.deadline_timestamp = current_time + circle.cycle_duration;
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+    // Check whether a circle is paused, directly or through the protocol-wide switch
+    fn is_paused(env: Env, circle_id: u64) -> Result<bool, Error>;
 
-        // 10. Mark as Paid in the old format for backward compatibility
-        env.storage().instance().set(&DataKey::Deposit(circle_id, user), &true);
-    }
+    // Replace the contract code; follow with migrate when the storage schema changed (Admin only)
+    fn upgrade(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), Error>;
 
-    fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
-        // 1. Authorization: The current admin must sign this transaction
-        current_admin.require_auth();
+    // Convert up to `limit` legacy circles to the current layout; the batch that converts the
+    // last one completes the migration and re-enables the other entry points (Admin only)
+    fn migrate(env: Env, admin: Address, limit: u32) -> Result<u32, Error>;
 
-        // 2. Get the current admin from storage
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("No admin set"));
+    // Move the listed legacy members into a migrated circle, with any contribution they made
+    // in the legacy cycle (Admin only)
+    fn migrate_members(env: Env, admin: Address, circle_id: u64, users: Vec<Address>) -> Result<u32, Error>;
 
-        // 3. Verify the caller is the current admin
-        if stored_admin != current_admin {
-            panic!("Caller is not the current admin");
-        }
+    // Get the storage layout version
+    fn get_schema_version(env: Env) -> Result<u32, Error>;
+    
+    // Set next cycle contribution amount (Circle organizers; protocol staff use propose_action)
+    fn set_next_cycle_amount(env: Env, caller: Address, circle_id: u64, amount: i128) -> Result<(), Error>;
+    
+    // Assign the current round's pot to the next recipient in rotation (pull pattern; circle organizers or operators)
+    fn distribute_funds(env: Env, caller: Address, circle_id: u64) -> Result<(), Error>;
+    
+    // Cover a member's missed contribution from their collateral, then the Group Reserve,
+    // once the round's grace period has passed (anyone); any uncovered rest becomes debt
+    fn mark_default(env: Env, circle_id: u64, user: Address) -> Result<(), Error>;
 
-        // 4. Update the admin in storage
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    // Repay debt recorded by mark_default; uncovered contributions are repaid to their rounds
+    // first, then repayments refill the Group Reserve
+    fn repay_debt(env: Env, user: Address, circle_id: u64, amount: i128) -> Result<(), Error>;
 
-        // 5. Emit the AdminChanged event
-        let event = AdminChangedEvent {
-            old_admin: current_admin,
-            new_admin: new_admin,
-        };
-        env.events().publish((Symbol::new(&env, "admin_changed"),), event);
-    }
+    // Get a member's outstanding debt in a circle
+    fn get_debt(env: Env, circle_id: u64, user: Address) -> Result<i128, Error>;
 
-    fn set_next_cycle_amount(env: Env, admin: Address, circle_id: u64, amount: i128) {
-        // 1. Authorization: The admin must sign this transaction
-        admin.require_auth();
+    // Get an address's savings track record across all circles
+    fn get_reputation(env: Env, user: Address) -> Result<Reputation, Error>;
 
-        // 2. Verify the caller is the admin
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("No admin set"));
-        
-        if stored_admin != admin {
-            panic!("Caller is not the admin");
-        }
+    // Claim funds from distribution, plus any collateral once the circle has completed or been cancelled
+    fn claim(env: Env, user: Address, circle_id: u64) -> Result<(), Error>;
 
-        // 3. Verify the circle exists
-        let _circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id))
-            .unwrap_or_else(|| panic!("Circle does not exist"));
+    // Get the collateral currently held for a member
+    fn get_collateral(env: Env, circle_id: u64, user: Address) -> Result<i128, Error>;
 
-        // 4. Set the next cycle amount
-        env.storage().instance().set(&DataKey::NextCycleAmount(circle_id), &amount);
-    }
+    // Place an open bid: the discount the user accepts to receive the current pot (Auction circles)
+    fn place_bid(env: Env, user: Address, circle_id: u64, discount: i128) -> Result<(), Error>;
 
-    fn distribute_funds(env: Env, admin: Address, circle_id: u64) {
-        // 1. Authorization: The admin must sign this transaction
-        admin.require_auth();
+    // Commit to a sealed bid as sha256(discount as 16 big-endian bytes || salt) (Sealed auction circles)
+    fn commit_bid(env: Env, user: Address, circle_id: u64, commitment: BytesN<32>) -> Result<(), Error>;
 
-        // 2. Verify the caller is the admin
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("No admin set"));
-        
-        if stored_admin != admin {
-            panic!("Caller is not the admin");
-        }
+    // Reveal a previously committed sealed bid (Sealed auction circles)
+    fn reveal_bid(env: Env, user: Address, circle_id: u64, discount: i128, salt: BytesN<32>) -> Result<(), Error>;
 
-        // 3. Get the circle info
-        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id))
-            .unwrap_or_else(|| panic!("Circle does not exist"));
+    // Get the best bid so far for a round
+    fn get_best_bid(env: Env, circle_id: u64, round: u32) -> Result<Option<BidInfo>, Error>;
 
-        // 4. Calculate total pool amount (simplified - in real implementation, 
-        // this would track actual deposited funds)
-        let total_pool = circle.contribution_amount * circle.member_count as i128;
+    // Get a circle's configuration and progress
+    fn get_circle(env: Env, circle_id: u64) -> Result<CircleInfo, Error>;
 
-        // 5. Calculate each member share
-        let share_per_member = total_pool / circle.member_count as i128;
+    // Get the number of circles created so far (circle IDs run from 1 to this count)
+    fn circle_count(env: Env) -> Result<u64, Error>;
 
-        // 6. Instead of distributing directly, set claimable balances
-        // Note: In a real implementation, you would need to track all members
-        // This is a simplified version that assumes we can iterate through members
-        // For the actual implementation, you would need a way to track all member addresses
-        
-        // For now, we will store the distribution info that can be claimed
-        // In a full implementation, you would iterate through all members
-        // and set their claimable balances
-        env.storage().instance().set(&DataKey::ClaimableBalance(circle_id, admin), &share_per_member);
-    }
+    // Get a circle's members in joining order
+    fn get_members(env: Env, circle_id: u64) -> Result<Vec<Address>, Error>;
 
-    fn claim(env: Env, user: Address, circle_id: u64) {
-        // 1. Authorization: The user must sign this transaction
-        user.require_auth();
+    // Get a member's payment standing for the current round, with their debt, claimable balance and collateral
+    fn get_member_status(env: Env, circle_id: u64, user: Address) -> Result<MemberStatus, Error>;
 
-        // 2. Get the claimable balance for this user
-        let claimable_balance: i128 = env.storage().instance().get(&DataKey::ClaimableBalance(circle_id, user.clone()))
-            .unwrap_or_else(|| panic!("No claimable balance for this user"));
+    // Get the payout a member can currently claim from a circle
+    fn get_claimable(env: Env, circle_id: u64, user: Address) -> Result<i128, Error>;
 
-        // 3. Get the circle info to get the token address
-        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id))
-            .unwrap_or_else(|| panic!("Circle does not exist"));
+    // Get the state of a round in a circle
+    fn get_round(env: Env, circle_id: u64, round: u32) -> Result<RoundInfo, Error>;
 
-        // 4. Create the token client
+    // Get the contributions actually received for a round in a circle
+    fn get_pot(env: Env, circle_id: u64, round: u32) -> Result<i128, Error>;
+
+    // Get the Group Reserve balance held for a circle in a given token
+    fn get_reserve(env: Env, circle_id: u64, token: Address) -> Result<i128, Error>;
+
+    // Get the due timestamp of a round in a circle's fixed schedule
+    fn get_round_deadline(env: Env, circle_id: u64, round: u32) -> Result<u64, Error>;
+
+    // Extend the storage TTL of a circle and its current round (anyone)
+    fn bump_circle(env: Env, circle_id: u64) -> Result<(), Error>;
+
+    // Extend the storage TTL of up to `limit` members' entries, starting at `start` in join order;
+    // returns how many members were bumped (anyone)
+    fn bump_members(env: Env, circle_id: u64, start: u32, limit: u32) -> Result<u32, Error>;
+}
+
+// --- HELPERS ---
+
+// Config lives in instance storage; circle and member data live in persistent entries whose
+// TTL is extended on every access so long-running circles are never archived mid-cycle
+fn load_entry<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+    value
+}
+
+fn save_entry<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn bump_entry(env: &Env, key: &DataKey) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+}
+
+fn publish_event<D: IntoVal<Env, Val>>(env: &Env, name: &str, data: D) {
+    env.events().publish((Symbol::new(env, name), EVENT_VERSION), data);
+}
+
+fn publish_circle_event<D: IntoVal<Env, Val>>(env: &Env, name: &str, circle_id: u64, data: D) {
+    env.events().publish((Symbol::new(env, name), EVENT_VERSION, circle_id), data);
+}
+
+fn load_circle(env: &Env, circle_id: u64) -> Result<CircleInfo, Error> {
+    load_entry(env, &DataKey::Circle(circle_id))
+        .ok_or(Error::CircleNotFound)
+}
+
+fn load_round(env: &Env, circle_id: u64, round: u32) -> Result<RoundInfo, Error> {
+    load_entry(env, &DataKey::Round(circle_id, round))
+        .ok_or(Error::RoundNotFound)
+}
+
+fn open_round(env: &Env, circle_id: u64, round: u32) {
+    // Paid flags are keyed by round, so opening a new round starts with nobody paid
+    let info = RoundInfo {
+        round,
+        status: RoundStatus::Open,
+        paid_count: 0,
+        pot: 0,
+        closed_at: 0,
+        opened_at: env.ledger().timestamp(),
+        opened_paused: paused_time(env, circle_id),
+    };
+    save_entry(env, &DataKey::Round(circle_id, round), &info);
+}
+
+fn adjust_circle_balance(env: &Env, circle_id: u64, delta: i128) -> Result<(), Error> {
+    let key = DataKey::CircleBalance(circle_id);
+    let balance: i128 = load_entry(env, &key).unwrap_or(0);
+    let updated = balance + delta;
+    if updated < 0 {
+        return Err(Error::InsufficientBalance);
+    }
+    save_entry(env, &key, &updated);
+    Ok(())
+}
+
+fn adjust_reserve(env: &Env, circle_id: u64, token: &Address, delta: i128) {
+    let key = DataKey::GroupReserve(circle_id, token.clone());
+    let balance: i128 = load_entry(env, &key).unwrap_or(0);
+    save_entry(env, &key, &(balance + delta));
+}
+
+fn close_round(env: &Env, round: &mut RoundInfo) {
+    round.status = RoundStatus::Closed;
+    round.closed_at = env.ledger().timestamp();
+}
+
+fn credit_claimable(env: &Env, circle_id: u64, user: Address, amount: i128) {
+    let key = DataKey::ClaimableBalance(circle_id, user);
+    let claimable: i128 = load_entry(env, &key).unwrap_or(0);
+    save_entry(env, &key, &(claimable + amount));
+}
+
+fn load_arrears(env: &Env, circle_id: u64, user: &Address) -> Vec<Arrear> {
+    load_entry(env, &DataKey::Arrears(circle_id, user.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Debt to the Group Reserve plus uncovered contributions still owed to round recipients
+fn outstanding_debt(env: &Env, circle_id: u64, user: &Address) -> i128 {
+    let debt: i128 = load_entry(env, &DataKey::Debt(circle_id, user.clone())).unwrap_or(0);
+    load_arrears(env, circle_id, user).iter().fold(debt, |total, arrear| total + arrear.amount)
+}
+
+// Discount shares accrue to every member through one running total rather than a write per
+// member; a member is owed whatever the total has grown by since their checkpoint
+fn pending_discount(env: &Env, circle_id: u64, user: &Address) -> i128 {
+    let total: i128 = load_entry(env, &DataKey::DiscountShares(circle_id)).unwrap_or(0);
+    let checkpoint: i128 = load_entry(env, &DataKey::DiscountCheckpoint(circle_id, user.clone())).unwrap_or(0);
+    total - checkpoint
+}
+
+// Returns the member's uncollected discount shares and marks them as collected
+fn collect_discount(env: &Env, circle_id: u64, user: &Address) -> i128 {
+    let pending = pending_discount(env, circle_id, user);
+    if pending > 0 {
+        let total: i128 = load_entry(env, &DataKey::DiscountShares(circle_id)).unwrap_or(0);
+        save_entry(env, &DataKey::DiscountCheckpoint(circle_id, user.clone()), &total);
+    }
+    pending
+}
+
+// Adds one round's share for every member except the recipient, who collects their earlier
+// shares now and skips this one; returns the recipient's earlier shares
+fn share_discount(env: &Env, circle_id: u64, recipient: &Address, share: i128) -> i128 {
+    let earlier = collect_discount(env, circle_id, recipient);
+    let key = DataKey::DiscountShares(circle_id);
+    let total: i128 = load_entry(env, &key).unwrap_or(0);
+    save_entry(env, &key, &(total + share));
+    save_entry(env, &DataKey::DiscountCheckpoint(circle_id, recipient.clone()), &(total + share));
+    earlier
+}
+
+// Bids close one window after the round closes; sealed auctions get a second window to reveal
+fn bidding_end(round: &RoundInfo, auction: &AuctionConfig) -> u64 {
+    let windows = if auction.sealed { 2 } else { 1 };
+    round.closed_at.saturating_add(auction.bid_window.saturating_mul(windows))
+}
+
+// Checks the user may bid in the current round and returns the round with the auction settings
+fn load_bidding_round(env: &Env, circle_id: u64, user: &Address) -> Result<(CircleInfo, RoundInfo, AuctionConfig), Error> {
+    let circle = load_circle(env, circle_id)?;
+    let auction = match &circle.config.payout_mode {
+        PayoutMode::Auction(auction) => auction.clone(),
+        PayoutMode::Rotation => return Err(Error::UnsupportedMode),
+    };
+    if circle.status != CircleStatus::Active {
+        return Err(Error::CircleNotActive);
+    }
+
+    let member: Member = load_entry(env, &DataKey::Member(circle_id, user.clone()))
+        .ok_or(Error::NotMember)?;
+    if member.has_received_payout {
+        return Err(Error::AlreadyReceived);
+    }
+
+    let round = load_round(env, circle_id, circle.current_round)?;
+    if round.status != RoundStatus::Closed {
+        return Err(Error::CycleNotComplete);
+    }
+    Ok((circle, round, auction))
+}
+
+// Records the bid if it beats the current best; ties go to the earlier bid
+fn submit_bid(env: &Env, circle: &CircleInfo, round: &RoundInfo, auction: &AuctionConfig, user: Address, discount: i128) -> Result<(), Error> {
+    let max_discount = round.pot * auction.max_discount_bps as i128 / BPS_DENOMINATOR;
+    if discount <= 0 || discount > max_discount {
+        return Err(Error::InvalidBid);
+    }
+
+    let bid_key = DataKey::Bid(circle.id, round.round);
+    let best: Option<BidInfo> = load_entry(env, &bid_key);
+    if let Some(best) = best {
+        if discount <= best.discount {
+            return Err(Error::BidTooLow);
+        }
+    }
+    save_entry(env, &bid_key, &BidInfo { bidder: user, discount });
+    Ok(())
+}
+
+// Members are credited with a completed circle as they settle its final round, so completing a
+// circle never rewrites every member's record in one call
+fn in_final_round(circle: &CircleInfo) -> bool {
+    circle.status == CircleStatus::Active && circle.current_recipient_index + 1 >= circle.member_count
+}
+
+fn load_reputation(env: &Env, user: &Address) -> Reputation {
+    load_entry(env, &DataKey::Reputation(user.clone())).unwrap_or_default()
+}
+
+fn meets_admission(reputation: &Reputation, criteria: &AdmissionCriteria) -> bool {
+    if reputation.circles_completed < criteria.min_completed_circles
+        || reputation.open_defaults > criteria.max_open_defaults
+    {
+        return false;
+    }
+
+    let missed = reputation.rounds_late as u64 + reputation.rounds_defaulted as u64;
+    let total = missed + reputation.rounds_on_time as u64;
+    total == 0 || missed * BPS_DENOMINATOR as u64 <= criteria.max_late_ratio_bps as u64 * total
+}
+
+fn save_reputation(env: &Env, user: &Address, reputation: &Reputation) {
+    save_entry(env, &DataKey::Reputation(user.clone()), reputation);
+}
+
+fn load_seed_state(env: &Env, circle_id: u64) -> SeedState {
+    load_entry(env, &DataKey::OrderSeed(circle_id))
+        .unwrap_or(SeedState { seed: BytesN::from_array(env, &[0u8; 32]), committed: 0, revealed: 0 })
+}
+
+fn load_payout_order(env: &Env, circle_id: u64) -> Result<Vec<Address>, Error> {
+    load_entry(env, &DataKey::PayoutOrder(circle_id))
+        .ok_or(Error::OrderNotSet)
+}
+
+// Closes membership: commit-reveal circles wait for seeds, others start right away
+fn lock_membership(env: &Env, circle: &mut CircleInfo) -> Result<(), Error> {
+    if circle.config.order_mode == OrderMode::CommitReveal {
+        circle.status = CircleStatus::Revealing;
+        publish_circle_event(env, "status_changed", circle.id, CircleStatusEvent { status: circle.status });
+        return Ok(());
+    }
+    start_rotation(env, circle)
+}
+
+fn start_rotation(env: &Env, circle: &mut CircleInfo) -> Result<(), Error> {
+    circle.status = CircleStatus::Active;
+    circle.start_timestamp = env.ledger().timestamp();
+    publish_circle_event(env, "status_changed", circle.id, CircleStatusEvent { status: circle.status });
+
+    // Only pauses from now on shift the schedule
+    let mut pause = load_circle_pause(env, circle.id);
+    pause.protocol_base = protocol_paused_time(env, circle.start_timestamp);
+    pause.total_paused = 0;
+    if pause.paused {
+        pause.paused_at = circle.start_timestamp;
+        pause.protocol_paused_at = pause.protocol_base;
+    }
+    save_entry(env, &DataKey::CirclePause(circle.id), &pause);
+
+    // Fix the payout order
+    let mut order: Vec<Address> = load_entry(env, &DataKey::CircleMembers(circle.id))
+        .ok_or(Error::NotEnoughMembers)?;
+    match circle.config.order_mode {
+        OrderMode::JoinOrder => {}
+        OrderMode::Prng => env.prng().shuffle(&mut order),
+        OrderMode::CommitReveal => {
+            let state = load_seed_state(env, circle.id);
+            env.prng().seed(Bytes::from(state.seed));
+            env.prng().shuffle(&mut order);
+        }
+    }
+    save_entry(env, &DataKey::PayoutOrder(circle.id), &order);
+
+    // Early contributions may already cover the first round
+    let mut round = load_round(env, circle.id, circle.current_round)?;
+    if round.paid_count >= circle.member_count {
+        close_round(env, &mut round);
+        save_entry(env, &DataKey::Round(circle.id, circle.current_round), &round);
+    }
+    Ok(())
+}
+
+// Round N is due at start + N * cycle_duration, but a round that opened late (delayed payout or a
+// long auction) still gets a full cycle from opening. Deadlines move back by the time the circle
+// spent paused, so penalty clocks freeze while paused
+fn round_deadline(env: &Env, circle: &CircleInfo, round: u32) -> u64 {
+    let paused = paused_time(env, circle.id);
+    let scheduled = circle.start_timestamp
+        .saturating_add(circle.cycle_duration.saturating_mul(round as u64))
+        .saturating_add(paused);
+    let info: Option<RoundInfo> = load_entry(env, &DataKey::Round(circle.id, round));
+    match info {
+        Some(info) => scheduled.max(
+            info.opened_at
+                .saturating_add(circle.cycle_duration)
+                .saturating_add(paused.saturating_sub(info.opened_paused)),
+        ),
+        None => scheduled,
+    }
+}
+
+fn load_protocol_pause(env: &Env) -> ProtocolPauseState {
+    env.storage().instance().get(&DataKey::ProtocolPause).unwrap_or_default()
+}
+
+fn load_circle_pause(env: &Env, circle_id: u64) -> CirclePauseState {
+    load_entry(env, &DataKey::CirclePause(circle_id)).unwrap_or_default()
+}
+
+// Total seconds the protocol has been paused up to now
+fn protocol_paused_time(env: &Env, now: u64) -> u64 {
+    let state = load_protocol_pause(env);
+    if state.paused {
+        state.total_paused + (now - state.paused_at)
+    } else {
+        state.total_paused
+    }
+}
+
+// Seconds of the circle's current pause during which the protocol was not also paused
+fn circle_only_paused(env: &Env, state: &CirclePauseState, now: u64) -> u64 {
+    let overlap = protocol_paused_time(env, now) - state.protocol_paused_at;
+    (now - state.paused_at) - overlap
+}
+
+// Protocol pauses from before a circle existed must not extend its fill deadline
+fn anchor_pause_clock(env: &Env, circle_id: u64) {
+    let state = CirclePauseState {
+        protocol_base: protocol_paused_time(env, env.ledger().timestamp()),
+        ..Default::default()
+    };
+    save_entry(env, &DataKey::CirclePause(circle_id), &state);
+}
+
+// Seconds a circle has been paused, by either switch, since it was created (or, once it is
+// active, since its rotation started)
+fn paused_time(env: &Env, circle_id: u64) -> u64 {
+    let now = env.ledger().timestamp();
+    let state = load_circle_pause(env, circle_id);
+    let mut paused = protocol_paused_time(env, now) - state.protocol_base + state.total_paused;
+    if state.paused {
+        paused += circle_only_paused(env, &state, now);
+    }
+    paused
+}
+
+// The fill deadline moves back by the time the circle spent paused, so downtime cannot get a
+// forming circle cancelled
+fn fill_deadline(env: &Env, circle: &CircleInfo) -> u64 {
+    circle.fill_deadline.saturating_add(paused_time(env, circle.id))
+}
+
+fn require_not_paused(env: &Env, circle_id: u64) -> Result<(), Error> {
+    if load_protocol_pause(env).paused || load_circle_pause(env, circle_id).paused {
+        return Err(Error::Paused);
+    }
+    Ok(())
+}
+
+fn validate_penalty_policy(policy: &PenaltyPolicy, cycle_duration: u64) -> Result<(), Error> {
+    if policy.flat_fee < 0 || policy.min_fee < 0 {
+        return Err(Error::InvalidPenaltyPolicy);
+    }
+    if policy.rate_bps > 0 && policy.max_escalation_days == 0 {
+        return Err(Error::InvalidPenaltyPolicy);
+    }
+    let max_total_bps = (policy.rate_bps as u64) * (policy.max_escalation_days as u64);
+    if max_total_bps > MAX_PENALTY_BPS as u64 {
+        return Err(Error::InvalidPenaltyPolicy);
+    }
+    if policy.grace_period >= cycle_duration {
+        return Err(Error::InvalidPenaltyPolicy);
+    }
+    Ok(())
+}
+
+fn calculate_penalty(policy: &PenaltyPolicy, amount: i128, deadline: u64, now: u64) -> i128 {
+    let penalty_start = deadline.saturating_add(policy.grace_period);
+    if now <= penalty_start {
+        return 0;
+    }
+
+    // Every started day past the grace period escalates the rate, up to the cap
+    let days_late = (now - penalty_start).div_ceil(SECONDS_PER_DAY);
+    let escalation_days = days_late.min(policy.max_escalation_days as u64) as i128;
+    let escalated = amount * policy.rate_bps as i128 * escalation_days / BPS_DENOMINATOR;
+
+    (policy.flat_fee + escalated).max(policy.min_fee)
+}
+
+fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+    let stored_admin: Address = env.storage().instance().get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+
+    if stored_admin != *admin {
+        return Err(Error::Unauthorized);
+    }
+    Ok(())
+}
+
+// Entry points stay closed until init has run and any legacy storage has been migrated;
+// every call keeps the contract instance and its config alive
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) || schema_version(env) < SCHEMA_VERSION {
+        return Err(Error::NotInitialized);
+    }
+    env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    Ok(())
+}
+
+// Deployments initialized before schema versioning have no SchemaVersion entry
+fn schema_version(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::SchemaVersion).unwrap_or(LEGACY_SCHEMA_VERSION)
+}
+
+fn legacy_field<T: TryFromVal<Env, Val>>(env: &Env, record: &Map<Symbol, Val>, name: &str) -> Result<T, Error> {
+    let value = record.get(Symbol::new(env, name)).ok_or(Error::InvalidLegacyData)?;
+    T::try_from_val(env, &value).map_err(|_| Error::InvalidLegacyData)
+}
+
+// Rewrites a legacy circle as a forming circle with the legacy 1% late fee; its members are
+// moved over with migrate_members, and new members can join until the fill deadline
+fn migrate_legacy_circle(env: &Env, circle_id: u64) -> Result<(), Error> {
+    let record: Map<Symbol, Val> = env.storage().instance().get(&DataKey::Circle(circle_id))
+        .ok_or(Error::CircleNotFound)?;
+
+    // Amounts were stored as u64 before the switch to i128
+    let amount: Val = legacy_field(env, &record, "contribution_amount")?;
+    let contribution_amount = i128::try_from_val(env, &amount)
+        .or_else(|_| u64::try_from_val(env, &amount).map(i128::from))
+        .map_err(|_| Error::InvalidLegacyData)?;
+    // The legacy contract did not validate these, so they are clamped to create_circle's bounds
+    let max_members = legacy_field::<u32>(env, &record, "max_members")?
+        .clamp(MIN_MEMBERS, MAX_MEMBERS);
+    let cycle_duration = legacy_field::<u64>(env, &record, "cycle_duration")?
+        .clamp(MIN_CYCLE_DURATION, MAX_CYCLE_DURATION);
+    let token: Address = legacy_field(env, &record, "token")?;
+
+    // Legacy paid flags were never reset and legacy payouts were not backed by deposits, so
+    // migrated amounts are limited to the tokens the contract actually holds. Nothing in the new
+    // layout can hold tokens until migration completes, so the balance is all legacy funds
+    let funds_key = DataKey::LegacyFunds(token.clone());
+    if !env.storage().persistent().has(&funds_key) {
+        let held = match token::Client::new(env, &token).try_balance(&env.current_contract_address()) {
+            Ok(Ok(held)) => held,
+            _ => return Err(Error::InvalidLegacyData),
+        };
+        save_entry(env, &funds_key, &held);
+    }
+
+    let circle = CircleInfo {
+        id: circle_id,
+        creator: legacy_field(env, &record, "creator")?,
+        contribution_amount,
+        max_members,
+        member_count: 0,
+        current_recipient_index: 0,
+        status: CircleStatus::Forming,
+        current_round: 1,
+        token,
+        start_timestamp: 0,
+        fill_deadline: env.ledger().timestamp().saturating_add(cycle_duration),
+        cycle_duration,
+        penalty: PenaltyPolicy {
+            rate_bps: 100,
+            flat_fee: 0,
+            min_fee: 0,
+            max_escalation_days: 1,
+            grace_period: 0,
+        },
+        config: CircleConfig {
+            min_members: MIN_MEMBERS,
+            fill_period: cycle_duration,
+            payout_mode: PayoutMode::Rotation,
+            order_mode: OrderMode::JoinOrder,
+            collateral_amount: 0,
+            admission: Admission::Open,
+        },
+    };
+    env.storage().instance().remove(&DataKey::Circle(circle_id));
+    save_entry(env, &DataKey::Circle(circle_id), &circle);
+    save_entry(env, &DataKey::LegacyCircle(circle_id), &true);
+    anchor_pause_clock(env, circle_id);
+    open_round(env, circle_id, 1);
+
+    // A pending amount change carries over to the next round as before
+    let next_amount_key = DataKey::NextCycleAmount(circle_id);
+    if let Some(amount) = env.storage().instance().get::<_, i128>(&next_amount_key) {
+        env.storage().instance().remove(&next_amount_key);
+        save_entry(env, &next_amount_key, &amount);
+    }
+    Ok(())
+}
+
+fn legacy_funds(env: &Env, token: &Address) -> i128 {
+    load_entry(env, &DataKey::LegacyFunds(token.clone())).unwrap_or(0)
+}
+
+// Draws up to `amount` from the legacy funds of a token and returns what was drawn
+fn draw_legacy_funds(env: &Env, token: &Address, amount: i128) -> i128 {
+    let available = legacy_funds(env, token);
+    let drawn = amount.min(available);
+    if drawn > 0 {
+        save_entry(env, &DataKey::LegacyFunds(token.clone()), &(available - drawn));
+    }
+    drawn
+}
+
+fn load_role_members(env: &Env, role: Role) -> Vec<Address> {
+    env.storage().instance().get(&DataKey::RoleMembers(role))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Super-admins implicitly hold every role
+fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    if load_role_members(env, role).contains(caller)
+        || load_role_members(env, Role::SuperAdmin).contains(caller)
+    {
+        return Ok(());
+    }
+    Err(Error::Unauthorized)
+}
+
+fn approval_threshold(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::ApprovalThreshold).unwrap_or(1)
+}
+
+// Role allowed to propose each protocol action
+fn action_role(action: &ProtocolAction) -> Role {
+    match action {
+        ProtocolAction::SetNextCycleAmount(_, _) => Role::Operator,
+        ProtocolAction::WithdrawReserve(_, _, _) => Role::Treasurer,
+        ProtocolAction::GrantRole(_, _)
+        | ProtocolAction::RevokeRole(_, _)
+        | ProtocolAction::SetApprovalThreshold(_) => Role::SuperAdmin,
+    }
+}
+
+// Checked when proposing and again when executing, since state may change in between
+fn validate_action(env: &Env, action: &ProtocolAction) -> Result<(), Error> {
+    match action {
+        ProtocolAction::SetNextCycleAmount(circle_id, amount) => {
+            load_circle(env, *circle_id)?;
+            if *amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+        }
+        ProtocolAction::WithdrawReserve(circle_id, _, amount) => {
+            let circle = load_circle(env, *circle_id)?;
+            if *amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            let reserve: i128 = load_entry(env, &DataKey::GroupReserve(*circle_id, circle.token))
+                .unwrap_or(0);
+            if reserve < *amount {
+                return Err(Error::InsufficientBalance);
+            }
+        }
+        ProtocolAction::GrantRole(role, account) => {
+            if load_role_members(env, *role).contains(account) {
+                return Err(Error::RoleAlreadyHeld);
+            }
+        }
+        ProtocolAction::RevokeRole(role, account) => {
+            let members = load_role_members(env, *role);
+            if !members.contains(account) {
+                return Err(Error::RoleNotHeld);
+            }
+            if *role == Role::SuperAdmin {
+                // The admin's seat moves only through transfer_admin / accept_admin
+                let admin: Address = env.storage().instance().get(&DataKey::Admin)
+                    .ok_or(Error::NotInitialized)?;
+                if *account == admin {
+                    return Err(Error::Unauthorized);
+                }
+                if members.len() - 1 < approval_threshold(env) {
+                    return Err(Error::InvalidThreshold);
+                }
+            }
+        }
+        ProtocolAction::SetApprovalThreshold(threshold) => {
+            if *threshold == 0 || *threshold > load_role_members(env, Role::SuperAdmin).len() {
+                return Err(Error::InvalidThreshold);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Only approvals from addresses that are still super-admins count towards the threshold
+fn execute_if_approved(env: &Env, proposal: &mut Proposal) -> Result<(), Error> {
+    let super_admins = load_role_members(env, Role::SuperAdmin);
+    let approvals = proposal.approvals.iter()
+        .filter(|approver| super_admins.contains(approver))
+        .count() as u32;
+    if approvals < approval_threshold(env) {
+        return Ok(());
+    }
+    validate_action(env, &proposal.action)?;
+
+    match proposal.action.clone() {
+        ProtocolAction::SetNextCycleAmount(circle_id, amount) => {
+            save_entry(env, &DataKey::NextCycleAmount(circle_id), &amount);
+            publish_circle_event(env, "next_amount_set", circle_id, NextAmountSetEvent { amount });
+        }
+        ProtocolAction::WithdrawReserve(circle_id, recipient, amount) => {
+            let circle = load_circle(env, circle_id)?;
+            adjust_reserve(env, circle_id, &circle.token, -amount);
+            let client = token::Client::new(env, &circle.token);
+            client.transfer(&env.current_contract_address(), &recipient, &amount);
+        }
+        ProtocolAction::GrantRole(role, account) => {
+            let mut members = load_role_members(env, role);
+            members.push_back(account);
+            env.storage().instance().set(&DataKey::RoleMembers(role), &members);
+        }
+        ProtocolAction::RevokeRole(role, account) => {
+            let mut members = load_role_members(env, role);
+            if let Some(index) = members.first_index_of(&account) {
+                members.remove(index);
+            }
+            env.storage().instance().set(&DataKey::RoleMembers(role), &members);
+        }
+        ProtocolAction::SetApprovalThreshold(threshold) => {
+            env.storage().instance().set(&DataKey::ApprovalThreshold, &threshold);
+        }
+    }
+
+    proposal.executed = true;
+    Ok(())
+}
+
+fn is_circle_organizer(env: &Env, circle: &CircleInfo, caller: &Address) -> bool {
+    if circle.creator == *caller {
+        return true;
+    }
+    let co_organizers: Vec<Address> = load_entry(env, &DataKey::CoOrganizers(circle.id))
+        .unwrap_or_else(|| Vec::new(env));
+    co_organizers.contains(caller)
+}
+
+// The circle's creator, its co-organizers and protocol operators may run a circle
+fn require_circle_manager(env: &Env, circle: &CircleInfo, caller: &Address) -> Result<(), Error> {
+    if is_circle_organizer(env, circle, caller) {
+        return Ok(());
+    }
+    require_role(env, caller, Role::Operator)
+}
+
+// --- IMPLEMENTATION ---
+
+#[contract]
+pub struct SoroSusu;
+
+#[contractimpl]
+impl SoroSusuTrait for SoroSusu {
+    fn init(env: Env, admin: Address, salt: BytesN<32>) -> Result<(), Error> {
+        // 1. Authorization: The admin being installed must sign this transaction and be the
+        // deployer, i.e. this contract's address must derive from the admin's address and the salt
+        admin.require_auth();
+        if env.deployer().with_address(admin.clone(), salt).deployed_address() != env.current_contract_address() {
+            return Err(Error::Unauthorized);
+        }
+
+        // 2. Initialization can only happen once
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        // 3. Initialize the circle counter to 0 if it doesn't exist
+        if !env.storage().instance().has(&DataKey::CircleCount) {
+            env.storage().instance().set(&DataKey::CircleCount, &0u64);
+        }
+        // 4. Record the schema version and set the admin, who starts as the only super-admin
+        // with a 1-of-1 approval threshold
+        env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::RoleMembers(Role::SuperAdmin), &Vec::from_array(&env, [admin]));
+        env.storage().instance().set(&DataKey::ApprovalThreshold, &1u32);
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64, penalty: PenaltyPolicy, config: CircleConfig) -> Result<u64, Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The creator must sign this transaction
+        creator.require_auth();
+
+        // 2. Validate the circle parameters, penalty policy and formation settings
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !(MIN_MEMBERS..=MAX_MEMBERS).contains(&max_members) {
+            return Err(Error::InvalidMemberCount);
+        }
+        if !(MIN_CYCLE_DURATION..=MAX_CYCLE_DURATION).contains(&cycle_duration) {
+            return Err(Error::InvalidCycleDuration);
+        }
+        if !matches!(token::Client::new(&env, &token).try_decimals(), Ok(Ok(_))) {
+            return Err(Error::InvalidToken);
+        }
+        validate_penalty_policy(&penalty, cycle_duration)?;
+        if config.min_members < MIN_MEMBERS || config.min_members > max_members || config.fill_period == 0 {
+            return Err(Error::InvalidCircleConfig);
+        }
+        if config.collateral_amount < 0 {
+            return Err(Error::InvalidCircleConfig);
+        }
+        if let Admission::Gated(criteria) = &config.admission {
+            if criteria.max_late_ratio_bps > MAX_BPS {
+                return Err(Error::InvalidCircleConfig);
+            }
+        }
+        if let PayoutMode::Auction(auction) = &config.payout_mode {
+            let windows = if auction.sealed { 2 } else { 1 };
+            if auction.bid_window == 0
+                || auction.bid_window.saturating_mul(windows) > cycle_duration
+                || auction.max_discount_bps == 0
+                || auction.max_discount_bps >= MAX_BPS
+            {
+                return Err(Error::InvalidCircleConfig);
+            }
+        }
+
+        // 3. Get the current Circle Count
+        let mut circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
+        
+        // 4. Increment the ID for the new circle
+        circle_count += 1;
+
+        // 5. Create the Circle Data Struct
+        let current_time = env.ledger().timestamp();
+        let new_circle = CircleInfo {
+            id: circle_count,
+            creator: creator.clone(),
+            contribution_amount: amount,
+            max_members,
+            member_count: 0,
+            current_recipient_index: 0,
+            status: CircleStatus::Forming,
+            current_round: 1,
+            token,
+            start_timestamp: 0,
+            fill_deadline: current_time.saturating_add(config.fill_period),
+            cycle_duration,
+            penalty,
+            config,
+        };
+
+        // 6. Save the Circle and the new Count, and open the first round for early contributions
+        save_entry(&env, &DataKey::Circle(circle_count), &new_circle);
+        env.storage().instance().set(&DataKey::CircleCount, &circle_count);
+        anchor_pause_clock(&env, circle_count);
+        open_round(&env, circle_count, 1);
+
+        publish_circle_event(&env, "circle_created", circle_count, CircleCreatedEvent {
+            creator,
+            token: new_circle.token,
+            contribution_amount: amount,
+            max_members,
+            cycle_duration,
+        });
+
+        // 7. Return the new ID
+        Ok(circle_count)
+    }
+
+    fn start_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The caller must sign this transaction
+        caller.require_auth();
+
+        // 2. Verify the caller manages this forming circle, and that it is not paused
+        let mut circle = load_circle(&env, circle_id)?;
+        require_circle_manager(&env, &circle, &caller)?;
+        require_not_paused(&env, circle_id)?;
+        if circle.status != CircleStatus::Forming {
+            return Err(Error::RoundInProgress);
+        }
+
+        // 3. Check the fill deadline and the creator-chosen minimum
+        if env.ledger().timestamp() > fill_deadline(&env, &circle) {
+            return Err(Error::FillDeadlinePassed);
+        }
+        if circle.member_count < circle.config.min_members {
+            return Err(Error::NotEnoughMembers);
+        }
+
+        // 4. Close membership and start the first round (once seeds are revealed for commit-reveal circles)
+        lock_membership(&env, &mut circle)?;
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+
+        Ok(())
+    }
+
+    fn add_co_organizer(env: Env, creator: Address, circle_id: u64, co_organizer: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The creator must sign this transaction
+        creator.require_auth();
+
+        // 2. Only the creator appoints co-organizers
+        let circle = load_circle(&env, circle_id)?;
+        if circle.creator != creator {
+            return Err(Error::Unauthorized);
+        }
+
+        // 3. Add the co-organizer if not already appointed and there is room
+        let key = DataKey::CoOrganizers(circle_id);
+        let mut co_organizers: Vec<Address> = load_entry(&env, &key)
+            .unwrap_or_else(|| Vec::new(&env));
+        if co_organizer == circle.creator || co_organizers.contains(&co_organizer) {
+            return Err(Error::AlreadyOrganizer);
+        }
+        if co_organizers.len() >= MAX_CO_ORGANIZERS {
+            return Err(Error::TooManyOrganizers);
+        }
+        co_organizers.push_back(co_organizer);
+        save_entry(&env, &key, &co_organizers);
+
+        Ok(())
+    }
+
+    fn remove_co_organizer(env: Env, creator: Address, circle_id: u64, co_organizer: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The creator must sign this transaction
+        creator.require_auth();
+
+        // 2. Only the creator revokes co-organizers
+        let circle = load_circle(&env, circle_id)?;
+        if circle.creator != creator {
+            return Err(Error::Unauthorized);
+        }
+
+        // 3. Remove the co-organizer
+        let key = DataKey::CoOrganizers(circle_id);
+        let mut co_organizers: Vec<Address> = load_entry(&env, &key)
+            .unwrap_or_else(|| Vec::new(&env));
+        let index = co_organizers.first_index_of(&co_organizer)
+            .ok_or(Error::NotOrganizer)?;
+        co_organizers.remove(index);
+        save_entry(&env, &key, &co_organizers);
+
+        Ok(())
+    }
+
+    fn get_co_organizers(env: Env, circle_id: u64) -> Result<Vec<Address>, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry(&env, &DataKey::CoOrganizers(circle_id))
+            .unwrap_or_else(|| Vec::new(&env)))
+    }
+
+    fn cancel_circle(env: Env, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Only circles that have not started by their fill deadline can be cancelled, and not while paused
+        let mut circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        if circle.status != CircleStatus::Forming && circle.status != CircleStatus::Revealing {
+            return Err(Error::RoundInProgress);
+        }
+        if env.ledger().timestamp() <= fill_deadline(&env, &circle) {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        // 2. Refund early contributions through claimable balances; a refunded contribution no
+        // longer counts towards the member's track record. In a commit-reveal circle, members who
+        // never revealed their seed stalled the start, so their collateral goes to the Group Reserve
+        let members: Vec<Address> = load_entry(&env, &DataKey::CircleMembers(circle_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        for member in members.iter() {
+            if circle.status == CircleStatus::Revealing {
+                let commitment: Option<SeedCommitment> = load_entry(&env, &DataKey::SeedCommit(circle_id, member.clone()));
+                let collateral_key = DataKey::Collateral(circle_id, member.clone());
+                let collateral: i128 = load_entry(&env, &collateral_key).unwrap_or(0);
+                if !commitment.is_some_and(|c| c.revealed) && collateral > 0 {
+                    env.storage().persistent().remove(&collateral_key);
+                    adjust_reserve(&env, circle_id, &circle.token, collateral);
+                }
+            }
+            let deposit_key = DataKey::Deposit(circle_id, circle.current_round, member.clone());
+            if env.storage().persistent().has(&deposit_key) {
+                let mut reputation = load_reputation(&env, &member);
+                reputation.rounds_on_time = reputation.rounds_on_time.saturating_sub(1);
+                save_reputation(&env, &member, &reputation);
+                credit_claimable(&env, circle_id, member, circle.contribution_amount);
+            }
+        }
+
+        // 3. Mark the circle as cancelled
+        circle.status = CircleStatus::Cancelled;
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+        publish_circle_event(&env, "status_changed", circle_id, CircleStatusEvent { status: circle.status });
+
+        Ok(())
+    }
+
+    fn commit_seed(env: Env, user: Address, circle_id: u64, commitment: BytesN<32>) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The member must sign this transaction
+        user.require_auth();
+
+        // 2. Commitments are accepted until the circle starts; reveals wait for every member's commitment
+        let circle = load_circle(&env, circle_id)?;
+        if circle.config.order_mode != OrderMode::CommitReveal {
+            return Err(Error::UnsupportedMode);
+        }
+        if circle.status != CircleStatus::Forming && circle.status != CircleStatus::Revealing {
+            return Err(Error::RoundInProgress);
+        }
+        if !env.storage().persistent().has(&DataKey::Member(circle_id, user.clone())) {
+            return Err(Error::NotMember);
+        }
+
+        // 3. Each member commits exactly once so nobody can re-commit after seeing a reveal
+        let commit_key = DataKey::SeedCommit(circle_id, user);
+        if env.storage().persistent().has(&commit_key) {
+            return Err(Error::SeedAlreadySubmitted);
+        }
+        save_entry(&env, &commit_key, &SeedCommitment { commitment, revealed: false });
+
+        let mut state = load_seed_state(&env, circle_id);
+        state.committed += 1;
+        save_entry(&env, &DataKey::OrderSeed(circle_id), &state);
+
+        Ok(())
+    }
+
+    fn reveal_seed(env: Env, user: Address, circle_id: u64, secret: BytesN<32>) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The member must sign this transaction
+        user.require_auth();
+
+        // 2. Reveals are accepted once membership is locked and before the fill deadline, and not while paused
+        let mut circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        if circle.config.order_mode != OrderMode::CommitReveal {
+            return Err(Error::UnsupportedMode);
+        }
+        if circle.status != CircleStatus::Revealing {
+            return Err(Error::CircleNotActive);
+        }
+        if env.ledger().timestamp() > fill_deadline(&env, &circle) {
+            return Err(Error::FillDeadlinePassed);
+        }
+        let mut state = load_seed_state(&env, circle_id);
+        if state.committed < circle.member_count {
+            return Err(Error::AwaitingCommitments);
+        }
+
+        // 3. Check the secret against the member's commitment
+        let commit_key = DataKey::SeedCommit(circle_id, user.clone());
+        let mut commitment: SeedCommitment = load_entry(&env, &commit_key)
+            .ok_or(Error::CommitmentMismatch)?;
+        if commitment.revealed {
+            return Err(Error::SeedAlreadySubmitted);
+        }
+        if env.crypto().sha256(&Bytes::from(secret.clone())) != commitment.commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+        commitment.revealed = true;
+        save_entry(&env, &commit_key, &commitment);
+
+        // 4. XOR the secret into the combined seed, so the order cannot depend on who reveals when
+        let mut seed = state.seed.to_array();
+        for (byte, secret_byte) in seed.iter_mut().zip(secret.to_array()) {
+            *byte ^= secret_byte;
+        }
+        state.seed = BytesN::from_array(&env, &seed);
+        state.revealed += 1;
+        save_entry(&env, &DataKey::OrderSeed(circle_id), &state);
+
+        // 5. The last reveal fixes the order and starts the first round
+        if state.revealed == circle.member_count {
+            start_rotation(&env, &mut circle)?;
+            save_entry(&env, &DataKey::Circle(circle_id), &circle);
+        }
+
+        Ok(())
+    }
+
+    fn get_payout_order(env: Env, circle_id: u64) -> Result<Vec<Address>, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        load_payout_order(&env, circle_id)
+    }
+
+    fn join_circle(env: Env, user: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The user MUST sign this transaction
+        user.require_auth();
+
+        // 2. Retrieve the circle data
+        let mut circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+
+        // 3. Check if the circle is full
+        if circle.member_count >= circle.max_members {
+            return Err(Error::CircleFull);
+        }
+
+        // 4. Check if user is already a member of this circle to prevent duplicates
+        let member_key = DataKey::Member(circle_id, user.clone());
+        if env.storage().persistent().has(&member_key) {
+            return Err(Error::AlreadyJoined);
+        }
+
+        // 4.1. Membership is locked once the circle has started
+        if circle.status != CircleStatus::Forming {
+            return Err(Error::RoundInProgress);
+        }
+        if env.ledger().timestamp() > fill_deadline(&env, &circle) {
+            return Err(Error::FillDeadlinePassed);
+        }
+
+        // 4.2. Gated circles only admit users whose track record meets the creator's criteria
+        if let Admission::Gated(criteria) = &circle.config.admission {
+            if !meets_admission(&load_reputation(&env, &user), criteria) {
+                return Err(Error::AdmissionCriteriaNotMet);
+            }
+        }
+
+        // 4.3. Take the security deposit if the circle requires one
+        if circle.config.collateral_amount > 0 {
+            let client = token::Client::new(&env, &circle.token);
+            if client.balance(&user) < circle.config.collateral_amount {
+                return Err(Error::InsufficientBalance);
+            }
+            client.transfer(
+                &user,
+                &env.current_contract_address(),
+                &circle.config.collateral_amount
+            );
+            save_entry(&env, &DataKey::Collateral(circle_id, user.clone()), &circle.config.collateral_amount);
+        }
+
+        // 5. Create and store the new member
+        let new_member = Member {
+            address: user.clone(),
+            has_contributed: false,
+            contribution_count: 0,
+            last_contribution_time: 0,
+            has_received_payout: false,
+        };
+        
+        // 6. Store the member, append them to the payout rotation and update circle count
+        save_entry(&env, &member_key, &new_member);
+        let members_key = DataKey::CircleMembers(circle_id);
+        let mut members: Vec<Address> = load_entry(&env, &members_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        members.push_back(user.clone());
+        save_entry(&env, &members_key, &members);
+        circle.member_count += 1;
+
+        let mut reputation = load_reputation(&env, &user);
+        reputation.circles_joined += 1;
+        save_reputation(&env, &user, &reputation);
+
+        publish_circle_event(&env, "member_joined", circle_id, MemberJoinedEvent {
+            member: user,
+            member_count: circle.member_count,
+        });
+
+        // 7. Membership closes and the first round starts as soon as the circle is full
+        if circle.member_count == circle.max_members {
+            lock_membership(&env, &mut circle)?;
+        }
+        
+        // 8. Save the updated circle back to storage
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+
+        Ok(())
+    }
+
+    fn leave_circle(env: Env, user: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The user must sign this transaction
+        user.require_auth();
+
+        // 2. Members can only leave before membership is locked
+        let mut circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        let member_key = DataKey::Member(circle_id, user.clone());
+        if !env.storage().persistent().has(&member_key) {
+            return Err(Error::NotMember);
+        }
+        if circle.status != CircleStatus::Forming {
+            return Err(Error::RoundInProgress);
+        }
+
+        // 3. Remove the member from the circle and the payout rotation
+        env.storage().persistent().remove(&member_key);
+        let members_key = DataKey::CircleMembers(circle_id);
+        let mut members: Vec<Address> = load_entry(&env, &members_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = members.first_index_of(&user) {
+            members.remove(index);
+        }
+        save_entry(&env, &members_key, &members);
+        circle.member_count -= 1;
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+
+        let mut reputation = load_reputation(&env, &user);
+        reputation.circles_joined = reputation.circles_joined.saturating_sub(1);
+        save_reputation(&env, &user, &reputation);
+
+        // 4. Withdraw their seed commitment so reveals only wait for the remaining members
+        let commit_key = DataKey::SeedCommit(circle_id, user.clone());
+        if env.storage().persistent().has(&commit_key) {
+            env.storage().persistent().remove(&commit_key);
+            let mut state = load_seed_state(&env, circle_id);
+            state.committed -= 1;
+            save_entry(&env, &DataKey::OrderSeed(circle_id), &state);
+        }
+
+        // 5. Refund an early contribution and the security deposit
+        let mut refund = 0i128;
+        let deposit_key = DataKey::Deposit(circle_id, circle.current_round, user.clone());
+        if env.storage().persistent().has(&deposit_key) {
+            env.storage().persistent().remove(&deposit_key);
+            let mut round = load_round(&env, circle_id, circle.current_round)?;
+            round.pot -= circle.contribution_amount;
+            round.paid_count -= 1;
+            save_entry(&env, &DataKey::Round(circle_id, circle.current_round), &round);
+            adjust_circle_balance(&env, circle_id, -circle.contribution_amount)?;
+            refund += circle.contribution_amount;
+
+            // A refunded early contribution no longer counts towards their track record
+            let mut reputation = load_reputation(&env, &user);
+            reputation.rounds_on_time = reputation.rounds_on_time.saturating_sub(1);
+            save_reputation(&env, &user, &reputation);
+        }
+        let collateral_key = DataKey::Collateral(circle_id, user.clone());
+        let collateral: i128 = load_entry(&env, &collateral_key).unwrap_or(0);
+        if collateral > 0 {
+            env.storage().persistent().remove(&collateral_key);
+            refund += collateral;
+        }
+        if refund > 0 {
+            let client = token::Client::new(&env, &circle.token);
+            client.transfer(&env.current_contract_address(), &user, &refund);
+        }
+
+        publish_circle_event(&env, "member_left", circle_id, MemberLeftEvent {
+            member: user,
+            member_count: circle.member_count,
+            refund,
+        });
+
+        Ok(())
+    }
+
+    fn deposit(env: Env, user: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The user must sign this!
+        user.require_auth();
+
+        // 2. Load the Circle Data; early contributions are accepted while it is forming
+        let circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        match circle.status {
+            CircleStatus::Forming | CircleStatus::Revealing | CircleStatus::Active => {}
+            CircleStatus::Completed => return Err(Error::CircleCompleted),
+            CircleStatus::Cancelled => return Err(Error::CircleNotActive),
+        }
+        let contribution_amount = circle.contribution_amount;
+
+        // 3. Check if user is actually a member
+        let member_key = DataKey::Member(circle_id, user.clone());
+        let mut member: Member = load_entry(&env, &member_key)
+            .ok_or(Error::NotMember)?;
+
+        // 3.1. Check the current round is collecting and the user has not paid it yet
+        let mut round = load_round(&env, circle_id, circle.current_round)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundNotOpen);
+        }
+        let deposit_key = DataKey::Deposit(circle_id, circle.current_round, user.clone());
+        if env.storage().persistent().has(&deposit_key) {
+            return Err(Error::AlreadyPaid);
+        }
+
+        // 4. Check if payment is late against the fixed schedule (after the grace period, like the
+        // penalty) and calculate the penalty if needed
+        let current_time = env.ledger().timestamp();
+        let mut penalty_amount = 0i128;
+        let mut is_late = false;
+        if circle.status == CircleStatus::Active {
+            let deadline = round_deadline(&env, &circle, circle.current_round);
+            is_late = current_time > deadline.saturating_add(circle.penalty.grace_period);
+            penalty_amount = calculate_penalty(&circle.penalty, contribution_amount, deadline, current_time);
+        }
+
+        // 5. Create the Token Client and make sure the user can cover contribution and penalty
+        let client = token::Client::new(&env, &circle.token);
+        if client.balance(&user) < contribution_amount + penalty_amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        // 6. Transfer the full amount from user, plus the penalty into the Group Reserve
+        client.transfer(
+            &user, 
+            &env.current_contract_address(), 
+            &contribution_amount
+        );
+
+        if penalty_amount > 0 {
+            client.transfer(
+                &user,
+                &env.current_contract_address(),
+                &penalty_amount
+            );
+            adjust_reserve(&env, circle_id, &circle.token, penalty_amount);
+        }
+
+        // 7. Update member contribution info
+        member.has_contributed = true;
+        member.contribution_count += 1;
+        member.last_contribution_time = current_time;
+        
+        // 8. Save updated member info and their track record
+        save_entry(&env, &member_key, &member);
+
+        let mut reputation = load_reputation(&env, &user);
+        if is_late {
+            reputation.rounds_late += 1;
+        } else {
+            reputation.rounds_on_time += 1;
+        }
+        if in_final_round(&circle) {
+            reputation.circles_completed += 1;
+        }
+        save_reputation(&env, &user, &reputation);
+
+        // 9. Mark as Paid for this round and credit the contribution to the pot;
+        // the round closes once every member has paid
+        save_entry(&env, &deposit_key, &true);
+        adjust_circle_balance(&env, circle_id, contribution_amount)?;
+        round.pot += contribution_amount;
+        round.paid_count += 1;
+        if circle.status == CircleStatus::Active && round.paid_count >= circle.member_count {
+            close_round(&env, &mut round);
+        }
+        save_entry(&env, &DataKey::Round(circle_id, circle.current_round), &round);
+
+        publish_circle_event(&env, "deposit", circle_id, DepositEvent {
+            member: user,
+            round: circle.current_round,
+            amount: contribution_amount,
+            penalty: penalty_amount,
+        });
+
+        Ok(())
+    }
+
+    fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The current admin must sign this transaction
+        current_admin.require_auth();
+
+        // 2. Verify the caller is the current admin
+        require_admin(&env, &current_admin)?;
+
+        // 3. Record the proposal; a new proposal replaces any pending one
+        let expires_at = env.ledger().timestamp() + ADMIN_TRANSFER_WINDOW;
+        let pending = PendingAdmin { new_admin: new_admin.clone(), expires_at };
+        env.storage().instance().set(&DataKey::PendingAdmin, &pending);
+
+        // 4. Emit the AdminTransferProposed event
+        let event = AdminTransferProposedEvent {
+            current_admin,
+            new_admin,
+            expires_at,
+        };
+        publish_event(&env, "admin_proposed", event);
+
+        Ok(())
+    }
+
+    fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The proposed admin must sign this transaction
+        new_admin.require_auth();
+
+        // 2. Verify the caller is the proposed admin and the proposal has not expired
+        let pending: PendingAdmin = env.storage().instance().get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().timestamp() > pending.expires_at {
+            return Err(Error::AdminTransferExpired);
+        }
+
+        // 3. Update the admin in storage and hand over its super-admin seat
+        let old_admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        let mut super_admins = load_role_members(&env, Role::SuperAdmin);
+        if let Some(index) = super_admins.first_index_of(&old_admin) {
+            super_admins.remove(index);
+        }
+        if !super_admins.contains(&new_admin) {
+            super_admins.push_back(new_admin.clone());
+        }
+        if super_admins.len() < approval_threshold(&env) {
+            return Err(Error::InvalidThreshold);
+        }
+        env.storage().instance().set(&DataKey::RoleMembers(Role::SuperAdmin), &super_admins);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        // 4. Emit the AdminChanged event
+        let event = AdminChangedEvent {
+            old_admin,
+            new_admin,
+        };
+        publish_event(&env, "admin_changed", event);
+
+        Ok(())
+    }
+
+    fn cancel_admin_transfer(env: Env, current_admin: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The current admin must sign this transaction
+        current_admin.require_auth();
+
+        // 2. Verify the caller is the current admin
+        require_admin(&env, &current_admin)?;
+
+        // 3. Remove the pending proposal
+        let pending: PendingAdmin = env.storage().instance().get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        // 4. Emit the AdminTransferCancelled event
+        let event = AdminTransferCancelledEvent {
+            current_admin,
+            new_admin: pending.new_admin,
+        };
+        publish_event(&env, "admin_cancelled", event);
+
+        Ok(())
+    }
+
+    fn get_pending_admin(env: Env) -> Result<PendingAdmin, Error> {
+        require_initialized(&env)?;
+
+        env.storage().instance().get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)
+    }
+
+    fn propose_action(env: Env, proposer: Address, action: ProtocolAction) -> Result<u64, Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The proposer must sign this transaction
+        proposer.require_auth();
+
+        // 2. Verify the proposer holds the role the action calls for and the action is valid
+        require_role(&env, &proposer, action_role(&action))?;
+        validate_action(&env, &action)?;
+
+        // 3. Record the proposal; a super-admin proposer counts as its first approval
+        let proposal_id: u64 = env.storage().instance().get(&DataKey::ProposalCount).unwrap_or(0) + 1;
+        env.storage().instance().set(&DataKey::ProposalCount, &proposal_id);
+        let mut approvals = Vec::new(&env);
+        if load_role_members(&env, Role::SuperAdmin).contains(&proposer) {
+            approvals.push_back(proposer.clone());
+        }
+        let mut proposal = Proposal {
+            id: proposal_id,
+            action,
+            proposer,
+            approvals,
+            expires_at: env.ledger().timestamp() + PROPOSAL_WINDOW,
+            executed: false,
+        };
+
+        // 4. Execute straight away if the threshold is already met
+        execute_if_approved(&env, &mut proposal)?;
+        save_entry(&env, &DataKey::Proposal(proposal_id), &proposal);
+
+        Ok(proposal_id)
+    }
+
+    fn approve_action(env: Env, approver: Address, proposal_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The approver must sign this transaction
+        approver.require_auth();
+
+        // 2. Verify the approver is a super-admin
+        if !load_role_members(&env, Role::SuperAdmin).contains(&approver) {
+            return Err(Error::Unauthorized);
+        }
+
+        // 3. Verify the proposal is still open and not yet approved by this super-admin
+        let mut proposal: Proposal = load_entry(&env, &DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)?;
+        if proposal.executed {
+            return Err(Error::ProposalExecuted);
+        }
+        if env.ledger().timestamp() > proposal.expires_at {
+            return Err(Error::ProposalExpired);
+        }
+        if proposal.approvals.contains(&approver) {
+            return Err(Error::AlreadyApproved);
+        }
+
+        // 4. Record the approval and execute once the threshold is met
+        proposal.approvals.push_back(approver);
+        execute_if_approved(&env, &mut proposal)?;
+        save_entry(&env, &DataKey::Proposal(proposal_id), &proposal);
+
+        Ok(())
+    }
+
+    fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        require_initialized(&env)?;
+
+        load_entry(&env, &DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    fn has_role(env: Env, role: Role, account: Address) -> Result<bool, Error> {
+        require_initialized(&env)?;
+
+        Ok(load_role_members(&env, role).contains(&account))
+    }
+
+    fn get_role_members(env: Env, role: Role) -> Result<Vec<Address>, Error> {
+        require_initialized(&env)?;
+
+        Ok(load_role_members(&env, role))
+    }
+
+    fn get_approval_threshold(env: Env) -> Result<u32, Error> {
+        require_initialized(&env)?;
+
+        Ok(approval_threshold(&env))
+    }
+
+    fn pause(env: Env, caller: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: A pauser must sign this transaction
+        caller.require_auth();
+        require_role(&env, &caller, Role::Pauser)?;
+
+        // 2. Start the pause; pausing twice keeps the original start time
+        let mut state = load_protocol_pause(&env);
+        if !state.paused {
+            state.paused = true;
+            state.paused_at = env.ledger().timestamp();
+            env.storage().instance().set(&DataKey::ProtocolPause, &state);
+            publish_event(&env, "paused", PauseEvent { caller });
+        }
+
+        Ok(())
+    }
+
+    fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: A pauser must sign this transaction
+        caller.require_auth();
+        require_role(&env, &caller, Role::Pauser)?;
+
+        // 2. Bank the paused time so every circle's deadlines shift by it
+        let mut state = load_protocol_pause(&env);
+        if state.paused {
+            state.total_paused += env.ledger().timestamp() - state.paused_at;
+            state.paused = false;
+            env.storage().instance().set(&DataKey::ProtocolPause, &state);
+            publish_event(&env, "unpaused", PauseEvent { caller });
+        }
+
+        Ok(())
+    }
+
+    fn pause_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: A pauser must sign this transaction; organizers may be members and
+        // could otherwise lock other members' claims or freeze their own penalty clocks
+        caller.require_auth();
+        require_role(&env, &caller, Role::Pauser)?;
+        load_circle(&env, circle_id)?;
+
+        // 2. Start the pause, remembering the protocol's paused time so overlaps are not counted twice
+        let mut state = load_circle_pause(&env, circle_id);
+        if !state.paused {
+            let now = env.ledger().timestamp();
+            state.paused = true;
+            state.paused_at = now;
+            state.protocol_paused_at = protocol_paused_time(&env, now);
+            save_entry(&env, &DataKey::CirclePause(circle_id), &state);
+            publish_circle_event(&env, "circle_paused", circle_id, PauseEvent { caller });
+        }
+
+        Ok(())
+    }
+
+    fn unpause_circle(env: Env, caller: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: A pauser must sign this transaction
+        caller.require_auth();
+        require_role(&env, &caller, Role::Pauser)?;
+        load_circle(&env, circle_id)?;
+
+        // 2. Bank the time the circle alone was paused
+        let mut state = load_circle_pause(&env, circle_id);
+        if state.paused {
+            state.total_paused += circle_only_paused(&env, &state, env.ledger().timestamp());
+            state.paused = false;
+            save_entry(&env, &DataKey::CirclePause(circle_id), &state);
+            publish_circle_event(&env, "circle_unpaused", circle_id, PauseEvent { caller });
+        }
+
+        Ok(())
+    }
+
+    fn is_paused(env: Env, circle_id: u64) -> Result<bool, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_protocol_pause(&env).paused || load_circle_pause(&env, circle_id).paused)
+    }
+
+    fn upgrade(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        // 1. Authorization: The admin must sign this transaction; this also works mid-migration
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        // 2. Swap the code; storage is converted separately by migrate
+        env.deployer().update_current_contract_wasm(wasm_hash);
+
+        Ok(())
+    }
+
+    fn migrate(env: Env, admin: Address, limit: u32) -> Result<u32, Error> {
+        // 1. Authorization: The admin must sign this transaction
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        // 2. Nothing to do once the storage is on the current schema
+        if schema_version(&env) >= SCHEMA_VERSION {
+            return Ok(0);
+        }
+
+        // 3. The legacy admin becomes the only super-admin
+        if !env.storage().instance().has(&DataKey::RoleMembers(Role::SuperAdmin)) {
+            env.storage().instance().set(&DataKey::RoleMembers(Role::SuperAdmin), &Vec::from_array(&env, [admin]));
+            env.storage().instance().set(&DataKey::ApprovalThreshold, &1u32);
+        }
+
+        // 4. Convert the next batch of circles
+        let circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
+        let mut cursor: u64 = env.storage().instance().get(&DataKey::MigrationCursor).unwrap_or(1);
+        let mut migrated = 0u32;
+        while cursor <= circle_count && migrated < limit {
+            migrate_legacy_circle(&env, cursor)?;
+            cursor += 1;
+            migrated += 1;
+        }
+
+        // 5. Record progress, or finish the migration
+        if cursor > circle_count {
+            env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+            env.storage().instance().remove(&DataKey::MigrationCursor);
+        } else {
+            env.storage().instance().set(&DataKey::MigrationCursor, &cursor);
+        }
+
+        Ok(migrated)
+    }
+
+    fn migrate_members(env: Env, admin: Address, circle_id: u64, users: Vec<Address>) -> Result<u32, Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The admin must sign this transaction
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        // 2. Only circles converted from the legacy layout that are still forming take legacy
+        // members, and not while paused since the last one starts the rotation
+        let mut circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        if !env.storage().persistent().has(&DataKey::LegacyCircle(circle_id)) {
+            return Err(Error::CircleNotFound);
+        }
+        if circle.status != CircleStatus::Forming {
+            return Err(Error::RoundInProgress);
+        }
+        let mut round = load_round(&env, circle_id, circle.current_round)?;
+        let members_key = DataKey::CircleMembers(circle_id);
+        let mut members: Vec<Address> = load_entry(&env, &members_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        for user in users.iter() {
+            // 3. Each user needs a legacy record and must not have joined yet; the record is
+            // consumed, since legacy members belonged to a single circle
+            let legacy_key = LegacyDataKey::Member(user.clone());
+            let legacy: LegacyMember = env.storage().instance().get(&legacy_key)
+                .ok_or(Error::NotMember)?;
+            let member_key = DataKey::Member(circle_id, user.clone());
+            if env.storage().persistent().has(&member_key) {
+                return Err(Error::AlreadyJoined);
+            }
+            if circle.member_count >= circle.max_members {
+                return Err(Error::CircleFull);
+            }
+
+            // 4. Store the member in the circle-scoped layout
+            let member = Member {
+                address: user.clone(),
+                has_contributed: legacy.has_contributed,
+                contribution_count: legacy.contribution_count,
+                last_contribution_time: legacy.last_contribution_time,
+                has_received_payout: false,
+            };
+            save_entry(&env, &member_key, &member);
+            env.storage().instance().remove(&legacy_key);
+            members.push_back(user.clone());
+            circle.member_count += 1;
+
+            publish_circle_event(&env, "member_joined", circle_id, MemberJoinedEvent {
+                member: user.clone(),
+                member_count: circle.member_count,
+            });
+
+            // 5. A legacy paid flag becomes an early contribution to the first round, credited as
+            // on time like any early contribution so a refund on cancel or leave evens out. Flags
+            // that legacy funds cannot back are dropped and the member pays the round normally
+            let mut reputation = load_reputation(&env, &user);
+            reputation.circles_joined += 1;
+            let legacy_deposit = LegacyDataKey::Deposit(circle_id, user.clone());
+            if env.storage().instance().has(&legacy_deposit) {
+                env.storage().instance().remove(&legacy_deposit);
+                let amount = circle.contribution_amount;
+                if legacy_funds(&env, &circle.token) >= amount {
+                    draw_legacy_funds(&env, &circle.token, amount);
+                    save_entry(&env, &DataKey::Deposit(circle_id, circle.current_round, user.clone()), &true);
+                    adjust_circle_balance(&env, circle_id, amount)?;
+                    round.pot += amount;
+                    round.paid_count += 1;
+                    reputation.rounds_on_time += 1;
+                }
+            }
+            save_reputation(&env, &user, &reputation);
+
+            // 6. Legacy claimable balances move to persistent storage as far as legacy funds back
+            // them; the tokens behind them now count towards the circle
+            let claimable_key = DataKey::ClaimableBalance(circle_id, user.clone());
+            let claimable: i128 = env.storage().instance().get(&claimable_key).unwrap_or(0);
+            if claimable > 0 {
+                env.storage().instance().remove(&claimable_key);
+                let backed = draw_legacy_funds(&env, &circle.token, claimable);
+                if backed > 0 {
+                    credit_claimable(&env, circle_id, user.clone(), backed);
+                    adjust_circle_balance(&env, circle_id, backed)?;
+                }
+            }
+        }
+
+        // 7. Save the membership, starting the rotation once the circle is full
+        save_entry(&env, &members_key, &members);
+        save_entry(&env, &DataKey::Round(circle_id, circle.current_round), &round);
+        if circle.member_count == circle.max_members {
+            lock_membership(&env, &mut circle)?;
+        }
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+
+        Ok(users.len())
+    }
+
+    fn get_schema_version(env: Env) -> Result<u32, Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        Ok(schema_version(&env))
+    }
+
+    fn set_next_cycle_amount(env: Env, caller: Address, circle_id: u64, amount: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The caller must sign this transaction
+        caller.require_auth();
+
+        // 2. Verify the caller organizes the circle and the amount is positive
+        let circle = load_circle(&env, circle_id)?;
+        if !is_circle_organizer(&env, &circle, &caller) {
+            return Err(Error::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // 3. Set the next cycle amount
+        save_entry(&env, &DataKey::NextCycleAmount(circle_id), &amount);
+        publish_circle_event(&env, "next_amount_set", circle_id, NextAmountSetEvent { amount });
+
+        Ok(())
+    }
+
+    fn distribute_funds(env: Env, caller: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The caller must sign this transaction
+        caller.require_auth();
+
+        // 2. Verify the caller manages the circle
+        let mut circle = load_circle(&env, circle_id)?;
+        require_circle_manager(&env, &circle, &caller)?;
+        require_not_paused(&env, circle_id)?;
+
+        // 3. Check the circle state
+        match circle.status {
+            CircleStatus::Active => {}
+            CircleStatus::Completed => return Err(Error::CircleCompleted),
+            CircleStatus::Forming | CircleStatus::Revealing | CircleStatus::Cancelled => return Err(Error::CircleNotActive),
+        }
+
+        // 3.1. Refuse to pay out until every member has paid for the current round
+        let mut round = load_round(&env, circle_id, circle.current_round)?;
+        if round.status != RoundStatus::Closed {
+            return Err(Error::CycleNotComplete);
+        }
+
+        // 4. Find the recipient for this round: the next member in the payout order, or the auction winner
+        let members = load_payout_order(&env, circle_id)?;
+        let (recipient, discount) = match &circle.config.payout_mode {
+            PayoutMode::Rotation => {
+                let recipient = members.get(circle.current_recipient_index)
+                    .ok_or(Error::NotEnoughMembers)?;
+                (recipient, 0i128)
+            }
+            PayoutMode::Auction(auction) => {
+                // Nobody is left to bid against on the final payout
+                let final_payout = circle.current_recipient_index + 1 >= circle.member_count;
+                if !final_payout && env.ledger().timestamp() < bidding_end(&round, auction) {
+                    return Err(Error::BiddingOpen);
+                }
+                let best: Option<BidInfo> = load_entry(&env, &DataKey::Bid(circle_id, circle.current_round));
+                match best {
+                    Some(bid) => (bid.bidder, bid.discount),
+                    None => {
+                        // Without bids the pot goes to the first member in the payout order who has not received it
+                        let mut fallback = None;
+                        for member in members.iter() {
+                            let info: Member = load_entry(&env, &DataKey::Member(circle_id, member.clone()))
+                                .ok_or(Error::NotMember)?;
+                            if !info.has_received_payout {
+                                fallback = Some(member);
+                                break;
+                            }
+                        }
+                        (fallback.ok_or(Error::NotEnoughMembers)?, 0i128)
+                    }
+                }
+            }
+        };
+
+        // 5. Pay out exactly what was received for this round, sharing any discount
+        // equally among the other members (the remainder stays with the recipient)
+        let total_pool = round.pot;
+        let mut payout = total_pool;
+        let mut earlier_shares = 0i128;
+        if discount > 0 {
+            let share = discount / (circle.member_count as i128 - 1);
+            earlier_shares = share_discount(&env, circle_id, &recipient, share);
+            let remainder = discount - share * (circle.member_count as i128 - 1);
+            payout = total_pool - discount + remainder;
+        }
+        credit_claimable(&env, circle_id, recipient.clone(), payout + earlier_shares);
+        publish_circle_event(&env, "payout_assigned", circle_id, PayoutEvent {
+            recipient: recipient.clone(),
+            round: circle.current_round,
+            amount: payout,
+            discount,
+        });
+
+        // 6. Record that the recipient has received the pot
+        let member_key = DataKey::Member(circle_id, recipient.clone());
+        let mut member: Member = load_entry(&env, &member_key)
+            .ok_or(Error::NotMember)?;
+        member.has_received_payout = true;
+        save_entry(&env, &member_key, &member);
+
+        // 7. Settle the round and advance the rotation; the circle ends once every member has received the pot
+        round.status = RoundStatus::Settled;
+        save_entry(&env, &DataKey::RoundRecipient(circle_id, circle.current_round), &recipient);
+        save_entry(&env, &DataKey::Round(circle_id, circle.current_round), &round);
+
+        circle.current_recipient_index += 1;
+        if circle.current_recipient_index >= circle.member_count {
+            circle.status = CircleStatus::Completed;
+            publish_circle_event(&env, "status_changed", circle_id, CircleStatusEvent { status: circle.status });
+        } else {
+            // 8. Open the next round, applying any pending contribution amount change
+            circle.current_round += 1;
+            let next_cycle_amount: Option<i128> = load_entry(&env, &DataKey::NextCycleAmount(circle_id));
+            if let Some(amount) = next_cycle_amount {
+                publish_circle_event(&env, "amount_changed", circle_id, AmountChangedEvent {
+                    round: circle.current_round,
+                    old_amount: circle.contribution_amount,
+                    new_amount: amount,
+                });
+                circle.contribution_amount = amount;
+                // Clear the next cycle amount since it has been applied
+                env.storage().persistent().remove(&DataKey::NextCycleAmount(circle_id));
+            }
+            open_round(&env, circle_id, circle.current_round);
+        }
+        save_entry(&env, &DataKey::Circle(circle_id), &circle);
+
+        Ok(())
+    }
+
+    fn mark_default(env: Env, circle_id: u64, user: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Only members of an active circle can be marked as defaulted, and not while deposits are paused
+        let circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+        if circle.status != CircleStatus::Active {
+            return Err(Error::CircleNotActive);
+        }
+        if !env.storage().persistent().has(&DataKey::Member(circle_id, user.clone())) {
+            return Err(Error::NotMember);
+        }
+
+        // 2. The member must still owe the current round after its grace period
+        let mut round = load_round(&env, circle_id, circle.current_round)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundNotOpen);
+        }
+        let deposit_key = DataKey::Deposit(circle_id, circle.current_round, user.clone());
+        if env.storage().persistent().has(&deposit_key) {
+            return Err(Error::AlreadyPaid);
+        }
+        let deadline = round_deadline(&env, &circle, circle.current_round);
+        if env.ledger().timestamp() <= deadline.saturating_add(circle.penalty.grace_period) {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        // 3. Cover the shortfall from the member's collateral first, then the Group Reserve
+        let shortfall = circle.contribution_amount;
+        let collateral_key = DataKey::Collateral(circle_id, user.clone());
+        let collateral: i128 = load_entry(&env, &collateral_key).unwrap_or(0);
+        let from_collateral = collateral.min(shortfall);
+        let reserve_key = DataKey::GroupReserve(circle_id, circle.token.clone());
+        let reserve: i128 = load_entry(&env, &reserve_key).unwrap_or(0);
+        let from_reserve = reserve.min(shortfall - from_collateral);
+        let uncovered = shortfall - from_collateral - from_reserve;
+
+        if from_collateral > 0 {
+            save_entry(&env, &collateral_key, &(collateral - from_collateral));
+        }
+        if from_reserve > 0 {
+            adjust_reserve(&env, circle_id, &circle.token, -from_reserve);
+        }
+
+        // 4. What the Group Reserve advanced is recorded as debt, repaid back into the reserve. The
+        // uncovered rest is owed to this round's recipient, so the round can still close and the
+        // circle move on without the member
+        let owed_before = outstanding_debt(&env, circle_id, &user);
+        if from_reserve > 0 {
+            let debt_key = DataKey::Debt(circle_id, user.clone());
+            let debt: i128 = load_entry(&env, &debt_key).unwrap_or(0);
+            save_entry(&env, &debt_key, &(debt + from_reserve));
+        }
+        if uncovered > 0 {
+            let mut arrears = load_arrears(&env, circle_id, &user);
+            arrears.push_back(Arrear { round: circle.current_round, amount: uncovered });
+            save_entry(&env, &DataKey::Arrears(circle_id, user.clone()), &arrears);
+        }
+        let new_debt = owed_before + from_reserve + uncovered;
+
+        let mut reputation = load_reputation(&env, &user);
+        reputation.rounds_defaulted += 1;
+        if in_final_round(&circle) {
+            reputation.circles_completed += 1;
+        }
+        if owed_before <= 0 && new_debt > 0 {
+            reputation.open_defaults += 1;
+        }
+        save_reputation(&env, &user, &reputation);
+
+        // 5. Count the round as resolved for this member and credit what was covered to the pot
+        let covered = from_collateral + from_reserve;
+        save_entry(&env, &deposit_key, &true);
+        save_entry(&env, &DataKey::Defaulted(circle_id, circle.current_round, user.clone()), &true);
+        adjust_circle_balance(&env, circle_id, covered)?;
+        round.pot += covered;
+        round.paid_count += 1;
+        if round.paid_count >= circle.member_count {
+            close_round(&env, &mut round);
+        }
+        save_entry(&env, &DataKey::Round(circle_id, circle.current_round), &round);
+
+        publish_circle_event(&env, "default_marked", circle_id, DefaultEvent {
+            member: user,
+            round: circle.current_round,
+            from_collateral,
+            from_reserve,
+            uncovered,
+            debt: new_debt,
+        });
+
+        Ok(())
+    }
+
+    fn repay_debt(env: Env, user: Address, circle_id: u64, amount: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The user must sign this transaction
+        user.require_auth();
+
+        // 2. Check the amount is positive and does not exceed the outstanding debt
+        let circle = load_circle(&env, circle_id)?;
+        let owed = outstanding_debt(&env, circle_id, &user);
+        if amount <= 0 || amount > owed {
+            return Err(Error::InvalidAmount);
+        }
+
+        // 3. Transfer the repayment to the contract
+        let client = token::Client::new(&env, &circle.token);
+        if client.balance(&user) < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        client.transfer(
+            &user,
+            &env.current_contract_address(),
+            &amount
+        );
+
+        // 4. Uncovered contributions are owed to other members, so they are repaid first, oldest
+        // round first: into the pot if the round has not been paid out yet, otherwise to its recipient
+        let mut remaining = amount;
+        let arrears_key = DataKey::Arrears(circle_id, user.clone());
+        let mut arrears = load_arrears(&env, circle_id, &user);
+        while remaining > 0 {
+            let Some(mut arrear) = arrears.first() else { break };
+            let part = arrear.amount.min(remaining);
+            let recipient: Option<Address> = load_entry(&env, &DataKey::RoundRecipient(circle_id, arrear.round));
+            match recipient {
+                Some(recipient) => credit_claimable(&env, circle_id, recipient, part),
+                None => {
+                    let mut round = load_round(&env, circle_id, arrear.round)?;
+                    round.pot += part;
+                    save_entry(&env, &DataKey::Round(circle_id, arrear.round), &round);
+                }
+            }
+            adjust_circle_balance(&env, circle_id, part)?;
+            remaining -= part;
+            arrear.amount -= part;
+            if arrear.amount == 0 {
+                arrears.pop_front();
+            } else {
+                arrears.set(0, arrear);
+            }
+        }
+        if arrears.is_empty() {
+            env.storage().persistent().remove(&arrears_key);
+        } else {
+            save_entry(&env, &arrears_key, &arrears);
+        }
+
+        // 5. The rest refills the Group Reserve
+        if remaining > 0 {
+            let debt_key = DataKey::Debt(circle_id, user.clone());
+            let debt: i128 = load_entry(&env, &debt_key).unwrap_or(0);
+            adjust_reserve(&env, circle_id, &circle.token, remaining);
+            save_entry(&env, &debt_key, &(debt - remaining));
+        }
+
+        let mut reputation = load_reputation(&env, &user);
+        reputation.debt_repaid += amount;
+        if owed == amount {
+            reputation.open_defaults = reputation.open_defaults.saturating_sub(1);
+        }
+        save_reputation(&env, &user, &reputation);
+
+        Ok(())
+    }
+
+    fn get_debt(env: Env, circle_id: u64, user: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(outstanding_debt(&env, circle_id, &user))
+    }
+
+    fn get_reputation(env: Env, user: Address) -> Result<Reputation, Error> {
+        require_initialized(&env)?;
+
+        Ok(load_reputation(&env, &user))
+    }
+
+    fn claim(env: Env, user: Address, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The user must sign this transaction
+        user.require_auth();
+
+        // 2. Get the circle info to get the token address
+        let circle = load_circle(&env, circle_id)?;
+        require_not_paused(&env, circle_id)?;
+
+        // 3. Get the claimable balance for this user with their auction discount shares, plus their
+        // collateral once every round is done
+        let claimable_balance: i128 = load_entry::<i128>(&env, &DataKey::ClaimableBalance(circle_id, user.clone()))
+            .unwrap_or(0) + collect_discount(&env, circle_id, &user);
+        let collateral_key = DataKey::Collateral(circle_id, user.clone());
+        let mut collateral = 0i128;
+        if circle.status == CircleStatus::Completed || circle.status == CircleStatus::Cancelled {
+            collateral = load_entry(&env, &collateral_key).unwrap_or(0);
+        }
+
+        if claimable_balance + collateral <= 0 {
+            return Err(Error::NoClaimableBalance);
+        }
+
+        // 4. Debit the circle's balance and create the token client
+        if claimable_balance > 0 {
+            adjust_circle_balance(&env, circle_id, -claimable_balance)?;
+        }
         let client = token::Client::new(&env, &circle.token);
 
         // 5. Transfer the funds to the user
         client.transfer(
             &env.current_contract_address(),
             &user,
-            &claimable_balance,
+            &(claimable_balance + collateral),
         );
 
-        // 6. Clear the claimable balance
-        env.storage().instance().set(&DataKey::ClaimableBalance(circle_id, user), &0i128);
+        // 6. Clear the claimable balance and released collateral
+        save_entry(&env, &DataKey::ClaimableBalance(circle_id, user.clone()), &0i128);
+        if collateral > 0 {
+            env.storage().persistent().remove(&collateral_key);
+        }
+
+        publish_circle_event(&env, "claimed", circle_id, ClaimEvent {
+            member: user,
+            amount: claimable_balance,
+            collateral,
+        });
+
+        Ok(())
+    }
+
+    fn place_bid(env: Env, user: Address, circle_id: u64, discount: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The bidder must sign this transaction
+        user.require_auth();
+
+        // 2. Check the user can bid in an open auction and the window is still running
+        let (circle, round, auction) = load_bidding_round(&env, circle_id, &user)?;
+        if auction.sealed {
+            return Err(Error::UnsupportedMode);
+        }
+        if env.ledger().timestamp() >= bidding_end(&round, &auction) {
+            return Err(Error::BiddingClosed);
+        }
+
+        // 3. Record the bid if it is the best so far
+        submit_bid(&env, &circle, &round, &auction, user, discount)
+    }
+
+    fn commit_bid(env: Env, user: Address, circle_id: u64, commitment: BytesN<32>) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The bidder must sign this transaction
+        user.require_auth();
+
+        // 2. Check the user can bid in a sealed auction and the commit window is still running
+        let (circle, round, auction) = load_bidding_round(&env, circle_id, &user)?;
+        if !auction.sealed {
+            return Err(Error::UnsupportedMode);
+        }
+        if env.ledger().timestamp() >= round.closed_at.saturating_add(auction.bid_window) {
+            return Err(Error::BiddingClosed);
+        }
+
+        // 3. Store (or replace) the commitment
+        save_entry(&env, &DataKey::BidCommit(circle.id, round.round, user), &commitment);
+
+        Ok(())
+    }
+
+    fn reveal_bid(env: Env, user: Address, circle_id: u64, discount: i128, salt: BytesN<32>) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Authorization: The bidder must sign this transaction
+        user.require_auth();
+
+        // 2. Check the user can bid in a sealed auction and the reveal window is running
+        let (circle, round, auction) = load_bidding_round(&env, circle_id, &user)?;
+        if !auction.sealed {
+            return Err(Error::UnsupportedMode);
+        }
+        let now = env.ledger().timestamp();
+        if now < round.closed_at.saturating_add(auction.bid_window) {
+            return Err(Error::BiddingOpen);
+        }
+        if now >= bidding_end(&round, &auction) {
+            return Err(Error::BiddingClosed);
+        }
+
+        // 3. Check the revealed bid matches the commitment
+        let commit_key = DataKey::BidCommit(circle.id, round.round, user.clone());
+        let commitment: BytesN<32> = load_entry(&env, &commit_key)
+            .ok_or(Error::CommitmentMismatch)?;
+        let mut preimage = Bytes::from_array(&env, &discount.to_be_bytes());
+        preimage.append(&Bytes::from(salt));
+        if env.crypto().sha256(&preimage) != commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+        env.storage().persistent().remove(&commit_key);
+
+        // 4. Record the bid if it is the best so far
+        submit_bid(&env, &circle, &round, &auction, user, discount)
+    }
+
+    fn get_best_bid(env: Env, circle_id: u64, round: u32) -> Result<Option<BidInfo>, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry(&env, &DataKey::Bid(circle_id, round)))
+    }
+
+    fn get_collateral(env: Env, circle_id: u64, user: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry(&env, &DataKey::Collateral(circle_id, user)).unwrap_or(0))
+    }
+
+    fn get_circle(env: Env, circle_id: u64) -> Result<CircleInfo, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)
+    }
+
+    fn circle_count(env: Env) -> Result<u64, Error> {
+        require_initialized(&env)?;
+
+        Ok(env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0))
+    }
+
+    fn get_members(env: Env, circle_id: u64) -> Result<Vec<Address>, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry(&env, &DataKey::CircleMembers(circle_id)).unwrap_or_else(|| Vec::new(&env)))
+    }
+
+    fn get_member_status(env: Env, circle_id: u64, user: Address) -> Result<MemberStatus, Error> {
+        require_initialized(&env)?;
+
+        // 1. Load the circle and the member record
+        let circle = load_circle(&env, circle_id)?;
+        let member: Member = load_entry(&env, &DataKey::Member(circle_id, user.clone()))
+            .ok_or(Error::NotMember)?;
+
+        // 2. A contribution is owed while the current round is collecting and the member has not paid it
+        let paid_current_round = env.storage().persistent()
+            .has(&DataKey::Deposit(circle_id, circle.current_round, user.clone()));
+        let mut penalty_due = 0i128;
+        let mut amount_due = 0i128;
+        let collecting = match circle.status {
+            CircleStatus::Forming | CircleStatus::Revealing | CircleStatus::Active => {
+                load_round(&env, circle_id, circle.current_round)?.status == RoundStatus::Open
+            }
+            CircleStatus::Completed | CircleStatus::Cancelled => false,
+        };
+        if collecting && !paid_current_round {
+            // 3. Penalties only accrue once the fixed schedule has started
+            if circle.status == CircleStatus::Active {
+                let deadline = round_deadline(&env, &circle, circle.current_round);
+                penalty_due = calculate_penalty(&circle.penalty, circle.contribution_amount, deadline, env.ledger().timestamp());
+            }
+            amount_due = circle.contribution_amount + penalty_due;
+        }
+
+        Ok(MemberStatus {
+            member,
+            round: circle.current_round,
+            paid_current_round,
+            penalty_due,
+            amount_due,
+            debt: outstanding_debt(&env, circle_id, &user),
+            claimable: load_entry::<i128>(&env, &DataKey::ClaimableBalance(circle_id, user.clone())).unwrap_or(0)
+                + pending_discount(&env, circle_id, &user),
+            collateral: load_entry(&env, &DataKey::Collateral(circle_id, user)).unwrap_or(0),
+        })
+    }
+
+    fn get_claimable(env: Env, circle_id: u64, user: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry::<i128>(&env, &DataKey::ClaimableBalance(circle_id, user.clone())).unwrap_or(0)
+            + pending_discount(&env, circle_id, &user))
+    }
+
+    fn get_round(env: Env, circle_id: u64, round: u32) -> Result<RoundInfo, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        load_round(&env, circle_id, round)
+    }
+
+    fn get_pot(env: Env, circle_id: u64, round: u32) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_round(&env, circle_id, round)?.pot)
+    }
+
+    fn get_reserve(env: Env, circle_id: u64, token: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        load_circle(&env, circle_id)?;
+        Ok(load_entry(&env, &DataKey::GroupReserve(circle_id, token)).unwrap_or(0))
+    }
+
+    fn get_round_deadline(env: Env, circle_id: u64, round: u32) -> Result<u64, Error> {
+        require_initialized(&env)?;
+
+        let circle = load_circle(&env, circle_id)?;
+        match circle.status {
+            CircleStatus::Active | CircleStatus::Completed => {}
+            CircleStatus::Forming | CircleStatus::Revealing | CircleStatus::Cancelled => return Err(Error::CircleNotActive),
+        }
+        if round == 0 || round > circle.member_count {
+            return Err(Error::RoundNotFound);
+        }
+        Ok(round_deadline(&env, &circle, round))
+    }
+
+    fn bump_circle(env: Env, circle_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+
+        // 1. Circle-level entries (loading the circle extends it)
+        let circle = load_circle(&env, circle_id)?;
+        let round = circle.current_round;
+        for key in [
+            DataKey::CircleMembers(circle_id),
+            DataKey::PayoutOrder(circle_id),
+            DataKey::CoOrganizers(circle_id),
+            DataKey::CircleBalance(circle_id),
+            DataKey::GroupReserve(circle_id, circle.token.clone()),
+            DataKey::NextCycleAmount(circle_id),
+            DataKey::DiscountShares(circle_id),
+            DataKey::CirclePause(circle_id),
+            DataKey::OrderSeed(circle_id),
+            DataKey::LegacyCircle(circle_id),
+            DataKey::Round(circle_id, round),
+            DataKey::Bid(circle_id, round),
+        ] {
+            bump_entry(&env, &key);
+        }
+
+        Ok(())
+    }
+
+    fn bump_members(env: Env, circle_id: u64, start: u32, limit: u32) -> Result<u32, Error> {
+        require_initialized(&env)?;
+
+        // 1. Pick one page of members; large circles are bumped over several calls
+        let circle = load_circle(&env, circle_id)?;
+        let round = circle.current_round;
+        let members: Vec<Address> = load_entry(&env, &DataKey::CircleMembers(circle_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let end = start.saturating_add(limit.min(MAX_BUMP_MEMBERS)).min(members.len());
+        if start >= end {
+            return Ok(0);
+        }
+
+        // 2. Each member's entries in this circle, and their track record
+        for member in members.slice(start..end).iter() {
+            for key in [
+                DataKey::Member(circle_id, member.clone()),
+                DataKey::Deposit(circle_id, round, member.clone()),
+                DataKey::ClaimableBalance(circle_id, member.clone()),
+                DataKey::DiscountCheckpoint(circle_id, member.clone()),
+                DataKey::Collateral(circle_id, member.clone()),
+                DataKey::Debt(circle_id, member.clone()),
+                DataKey::Arrears(circle_id, member.clone()),
+                DataKey::SeedCommit(circle_id, member.clone()),
+                DataKey::BidCommit(circle_id, round, member.clone()),
+                DataKey::Reputation(member.clone()),
+            ] {
+                bump_entry(&env, &key);
+            }
+        }
+
+        Ok(end - start)
     }
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,875 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
+
+const DAY: u64 = 86_400;
+
+// --- HELPERS ---
+
+// Deploys the contract at the address the admin derives with a fixed salt, initializes it and
+// registers a token the admin can mint
+fn setup(env: &Env) -> (SoroSusuClient<'_>, Address, Address) {
+    env.mock_all_auths();
+    let admin = Address::generate(env);
+    let salt = BytesN::from_array(env, &[1; 32]);
+    let contract_id = env.deployer().with_address(admin.clone(), salt.clone()).deployed_address();
+    env.register_contract(&contract_id, SoroSusu);
+    let client = SoroSusuClient::new(env, &contract_id);
+    client.init(&admin, &salt);
+    let token = env.register_stellar_asset_contract(admin.clone());
+    (client, admin, token)
+}
+
+fn fund(env: &Env, token: &Address, user: &Address, amount: i128) {
+    StellarAssetClient::new(env, token).mint(user, &amount);
+}
+
+fn balance(env: &Env, token: &Address, user: &Address) -> i128 {
+    TokenClient::new(env, token).balance(user)
+}
+
+fn no_penalty() -> PenaltyPolicy {
+    PenaltyPolicy { rate_bps: 0, flat_fee: 0, min_fee: 0, max_escalation_days: 0, grace_period: 0 }
+}
+
+fn rotation_config(min_members: u32) -> CircleConfig {
+    CircleConfig {
+        min_members,
+        fill_period: 7 * DAY,
+        payout_mode: PayoutMode::Rotation,
+        order_mode: OrderMode::JoinOrder,
+        collateral_amount: 0,
+        admission: Admission::Open,
+    }
+}
+
+// Creates a rotation circle of `size` members contributing 100 per weekly round
+fn create(client: &SoroSusuClient, creator: &Address, token: &Address, size: u32) -> u64 {
+    client.create_circle(creator, &100, &size, token, &(7 * DAY), &no_penalty(), &rotation_config(size))
+}
+
+// Generates `count` funded users and joins them to the circle in order
+fn join(env: &Env, client: &SoroSusuClient, token: &Address, circle_id: u64, count: u32) -> Vec<Address> {
+    let mut users = Vec::new(env);
+    for _ in 0..count {
+        let user = Address::generate(env);
+        fund(env, token, &user, 10_000);
+        client.join_circle(&user, &circle_id);
+        users.push_back(user);
+    }
+    users
+}
+
+// --- TESTS ---
+
+#[test]
+fn rotation_pays_each_member_the_full_pot_in_turn() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 3);
+    let users = join(&env, &client, &token, circle_id, 3);
+
+    for round in 0..3 {
+        for user in users.iter() {
+            client.deposit(&user, &circle_id);
+        }
+        client.distribute_funds(&admin, &circle_id);
+        let recipient = users.get(round).unwrap();
+        assert_eq!(client.get_claimable(&circle_id, &recipient), 300);
+        client.claim(&recipient, &circle_id);
+        assert_eq!(client.get_circle(&circle_id).current_recipient_index, round + 1);
+    }
+
+    assert_eq!(client.get_circle(&circle_id).status, CircleStatus::Completed);
+    assert_eq!(client.try_distribute_funds(&admin, &circle_id), Err(Ok(Error::CircleCompleted)));
+    for user in users.iter() {
+        assert_eq!(balance(&env, &token, &user), 10_000);
+    }
+}
+
+#[test]
+fn membership_is_scoped_per_circle() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let first = create(&client, &admin, &token, 2);
+    let second = create(&client, &admin, &token, 2);
+    let user = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    fund(&env, &token, &user, 10_000);
+
+    client.join_circle(&user, &first);
+    client.join_circle(&user, &second);
+    client.deposit(&user, &first);
+
+    assert_eq!(client.get_members(&first), Vec::from_array(&env, [user.clone()]));
+    assert!(client.get_member_status(&first, &user).paid_current_round);
+    assert!(!client.get_member_status(&second, &user).paid_current_round);
+    assert_eq!(client.try_deposit(&outsider, &first), Err(Ok(Error::NotMember)));
+}
+
+#[test]
+fn failures_return_typed_errors() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 3);
+    let users = join(&env, &client, &token, circle_id, 1);
+    let user = users.get(0).unwrap();
+    let stranger = Address::generate(&env);
+
+    assert_eq!(client.try_join_circle(&user, &circle_id), Err(Ok(Error::AlreadyJoined)));
+    assert_eq!(client.try_join_circle(&user, &99), Err(Ok(Error::CircleNotFound)));
+    assert_eq!(client.try_claim(&user, &circle_id), Err(Ok(Error::NoClaimableBalance)));
+    assert_eq!(client.try_transfer_admin(&stranger, &user), Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn payout_waits_for_every_contribution_in_the_round() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 2);
+    let users = join(&env, &client, &token, circle_id, 2);
+
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    assert_eq!(client.try_deposit(&users.get(0).unwrap(), &circle_id), Err(Ok(Error::AlreadyPaid)));
+    assert_eq!(client.try_distribute_funds(&admin, &circle_id), Err(Ok(Error::CycleNotComplete)));
+    assert_eq!(client.get_round(&circle_id, &1).status, RoundStatus::Open);
+
+    client.deposit(&users.get(1).unwrap(), &circle_id);
+    assert_eq!(client.get_round(&circle_id, &1).status, RoundStatus::Closed);
+
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_round(&circle_id, &1).status, RoundStatus::Settled);
+    let next = client.get_round(&circle_id, &2);
+    assert_eq!(next.status, RoundStatus::Open);
+    assert_eq!(next.paid_count, 0);
+    assert!(matches!(client.try_get_round(&circle_id, &3), Err(Ok(Error::RoundNotFound))));
+}
+
+#[test]
+fn pot_tracks_contributions_actually_received() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 2);
+    let users = join(&env, &client, &token, circle_id, 2);
+
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    assert_eq!(client.get_pot(&circle_id, &1), 100);
+    client.deposit(&users.get(1).unwrap(), &circle_id);
+    client.set_next_cycle_amount(&admin, &circle_id, &150);
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &users.get(0).unwrap()), 200);
+
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    assert_eq!(client.get_pot(&circle_id, &2), 300);
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &users.get(1).unwrap()), 300);
+}
+
+#[test]
+fn late_penalty_is_collected_into_the_circle_reserve() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let penalty = PenaltyPolicy { rate_bps: 0, flat_fee: 10, min_fee: 0, max_escalation_days: 0, grace_period: 0 };
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &penalty, &rotation_config(2));
+    let other_id = create(&client, &admin, &token, 2);
+    let users = join(&env, &client, &token, circle_id, 2);
+    let late = users.get(1).unwrap();
+
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    env.ledger().with_mut(|l| l.timestamp += 8 * DAY);
+    client.deposit(&late, &circle_id);
+
+    assert_eq!(balance(&env, &token, &late), 10_000 - 110);
+    assert_eq!(client.get_reserve(&circle_id, &token), 10);
+    assert_eq!(client.get_reserve(&other_id, &token), 0);
+    assert_eq!(client.get_pot(&circle_id, &1), 200);
+}
+
+#[test]
+fn penalty_policy_is_validated_and_escalates_daily() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let cycle = 7 * DAY;
+    let long_grace = PenaltyPolicy { grace_period: cycle, ..no_penalty() };
+    let uncapped = PenaltyPolicy { rate_bps: 100, max_escalation_days: 0, ..no_penalty() };
+    let excessive = PenaltyPolicy { rate_bps: 5_000, max_escalation_days: 3, ..no_penalty() };
+    for policy in [long_grace, uncapped, excessive] {
+        assert_eq!(
+            client.try_create_circle(&admin, &100, &2, &token, &cycle, &policy, &rotation_config(2)),
+            Err(Ok(Error::InvalidPenaltyPolicy))
+        );
+    }
+
+    // 2% per started day after a one-day grace period, capped at 3 days, at least 5
+    let policy = PenaltyPolicy { rate_bps: 200, flat_fee: 0, min_fee: 5, max_escalation_days: 3, grace_period: DAY };
+    let circle_id = client.create_circle(&admin, &1_000, &2, &token, &cycle, &policy, &rotation_config(2));
+    let users = join(&env, &client, &token, circle_id, 2);
+
+    env.ledger().with_mut(|l| l.timestamp = cycle + DAY);
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    assert_eq!(client.get_reserve(&circle_id, &token), 0);
+
+    env.ledger().with_mut(|l| l.timestamp = cycle + 6 * DAY);
+    client.deposit(&users.get(1).unwrap(), &circle_id);
+    assert_eq!(client.get_reserve(&circle_id, &token), 60);
+}
+
+#[test]
+fn round_deadlines_follow_the_start_time_and_late_rounds_get_a_full_cycle() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let penalty = PenaltyPolicy { rate_bps: 0, flat_fee: 10, min_fee: 0, max_escalation_days: 0, grace_period: 0 };
+    let cycle = 7 * DAY;
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &cycle, &penalty, &rotation_config(2));
+    env.ledger().with_mut(|l| l.timestamp = DAY);
+    let users = join(&env, &client, &token, circle_id, 2);
+
+    // Round N is due at start + N * cycle, however early anyone pays
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    assert_eq!(client.get_round_deadline(&circle_id, &1), DAY + cycle);
+    assert_eq!(client.get_round_deadline(&circle_id, &2), DAY + 2 * cycle);
+
+    // The payout for round 1 happens after round 2 was due; round 2 still gets a full cycle
+    client.deposit(&users.get(1).unwrap(), &circle_id);
+    let opened = DAY + 3 * cycle;
+    env.ledger().with_mut(|l| l.timestamp = opened);
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_round_deadline(&circle_id, &2), opened + cycle);
+    assert_eq!(client.try_mark_default(&circle_id, &users.get(0).unwrap()), Err(Ok(Error::DeadlineNotReached)));
+
+    env.ledger().with_mut(|l| l.timestamp = opened + DAY);
+    client.deposit(&users.get(0).unwrap(), &circle_id);
+    assert_eq!(client.get_reserve(&circle_id, &token), 0);
+}
+
+#[test]
+fn circle_starts_when_full_or_is_cancelled_with_refunds() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+
+    // Starts on its own once full
+    let full_id = create(&client, &admin, &token, 2);
+    join(&env, &client, &token, full_id, 2);
+    assert_eq!(client.get_circle(&full_id).status, CircleStatus::Active);
+
+    // Creator may start early once min_members have joined
+    let early_id = client.create_circle(&admin, &100, &4, &token, &(7 * DAY), &no_penalty(), &rotation_config(2));
+    join(&env, &client, &token, early_id, 1);
+    assert_eq!(client.try_start_circle(&admin, &early_id), Err(Ok(Error::NotEnoughMembers)));
+    join(&env, &client, &token, early_id, 1);
+    client.start_circle(&admin, &early_id);
+    assert_eq!(client.get_circle(&early_id).status, CircleStatus::Active);
+
+    // Cancelled after the fill deadline, refunding early contributions
+    let stale_id = create(&client, &admin, &token, 3);
+    let users = join(&env, &client, &token, stale_id, 1);
+    let user = users.get(0).unwrap();
+    client.deposit(&user, &stale_id);
+    assert_eq!(client.try_cancel_circle(&stale_id), Err(Ok(Error::DeadlineNotReached)));
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + 1);
+    assert_eq!(client.try_join_circle(&Address::generate(&env), &stale_id), Err(Ok(Error::FillDeadlinePassed)));
+    client.cancel_circle(&stale_id);
+    assert_eq!(client.get_circle(&stale_id).status, CircleStatus::Cancelled);
+    client.claim(&user, &stale_id);
+    assert_eq!(balance(&env, &token, &user), 10_000);
+}
+
+#[test]
+fn create_circle_validates_its_parameters() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let policy = no_penalty();
+    let config = rotation_config(2);
+    let week = 7 * DAY;
+
+    assert_eq!(client.try_create_circle(&admin, &0, &2, &token, &week, &policy, &config), Err(Ok(Error::InvalidAmount)));
+    assert_eq!(client.try_create_circle(&admin, &100, &1, &token, &week, &policy, &config), Err(Ok(Error::InvalidMemberCount)));
+    assert_eq!(client.try_create_circle(&admin, &100, &101, &token, &week, &policy, &config), Err(Ok(Error::InvalidMemberCount)));
+    assert_eq!(client.try_create_circle(&admin, &100, &2, &token, &0, &policy, &config), Err(Ok(Error::InvalidCycleDuration)));
+    assert_eq!(client.try_create_circle(&admin, &100, &2, &token, &(366 * DAY), &policy, &config), Err(Ok(Error::InvalidCycleDuration)));
+    let not_a_token = Address::generate(&env);
+    assert_eq!(client.try_create_circle(&admin, &100, &2, &not_a_token, &week, &policy, &config), Err(Ok(Error::InvalidToken)));
+    let bad_config = CircleConfig { min_members: 3, ..rotation_config(2) };
+    assert_eq!(client.try_create_circle(&admin, &100, &2, &token, &week, &policy, &bad_config), Err(Ok(Error::InvalidCircleConfig)));
+}
+
+#[test]
+fn auction_winner_receives_the_pot_minus_a_shared_discount() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let auction = AuctionConfig { bid_window: DAY, sealed: false, max_discount_bps: 2_000 };
+    let config = CircleConfig { payout_mode: PayoutMode::Auction(auction), ..rotation_config(3) };
+    let circle_id = client.create_circle(&admin, &100, &3, &token, &(7 * DAY), &no_penalty(), &config);
+    let users = join(&env, &client, &token, circle_id, 3);
+    let (first, second, third) = (users.get(0).unwrap(), users.get(1).unwrap(), users.get(2).unwrap());
+
+    assert_eq!(client.try_place_bid(&first, &circle_id, &10), Err(Ok(Error::CycleNotComplete)));
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    assert_eq!(client.try_place_bid(&first, &circle_id, &100), Err(Ok(Error::InvalidBid)));
+    client.place_bid(&first, &circle_id, &20);
+    client.place_bid(&third, &circle_id, &30);
+    assert_eq!(client.try_place_bid(&second, &circle_id, &30), Err(Ok(Error::BidTooLow)));
+    assert_eq!(client.try_distribute_funds(&admin, &circle_id), Err(Ok(Error::BiddingOpen)));
+
+    env.ledger().with_mut(|l| l.timestamp += DAY);
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &third), 270);
+    assert_eq!(client.get_claimable(&circle_id, &first), 15);
+    assert_eq!(client.get_claimable(&circle_id, &second), 15);
+    client.claim(&first, &circle_id);
+    assert_eq!(balance(&env, &token, &first), 10_000 - 100 + 15);
+
+    // Shares keep accruing per round; a winner collects their earlier shares with the pot
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    assert_eq!(client.try_place_bid(&third, &circle_id, &10), Err(Ok(Error::AlreadyReceived)));
+    client.place_bid(&second, &circle_id, &10);
+    env.ledger().with_mut(|l| l.timestamp += DAY);
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &second), 290 + 15);
+    assert_eq!(client.get_claimable(&circle_id, &first), 5);
+    assert_eq!(client.get_claimable(&circle_id, &third), 275);
+}
+
+#[test]
+fn payout_order_can_be_shuffled_by_member_seeds() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let config = CircleConfig { order_mode: OrderMode::CommitReveal, ..rotation_config(3) };
+    let circle_id = client.create_circle(&admin, &100, &3, &token, &(7 * DAY), &no_penalty(), &config);
+    let users = join(&env, &client, &token, circle_id, 3);
+    let mirror_id = client.create_circle(&admin, &100, &3, &token, &(7 * DAY), &no_penalty(), &config);
+    for user in users.iter() {
+        client.join_circle(&user, &mirror_id);
+    }
+    assert_eq!(client.try_get_payout_order(&circle_id), Err(Ok(Error::OrderNotSet)));
+    assert_eq!(client.get_circle(&circle_id).status, CircleStatus::Revealing);
+
+    let mut secrets = Vec::new(&env);
+    for (i, user) in users.iter().enumerate() {
+        let secret = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        let commitment = env.crypto().sha256(&Bytes::from(secret.clone()));
+        client.commit_seed(&user, &circle_id, &commitment);
+        client.commit_seed(&user, &mirror_id, &commitment);
+        secrets.push_back(secret);
+    }
+
+    let first = users.get(0).unwrap();
+    assert_eq!(client.try_commit_seed(&first, &circle_id, &secrets.get(0).unwrap()), Err(Ok(Error::SeedAlreadySubmitted)));
+    assert_eq!(client.try_reveal_seed(&first, &circle_id, &secrets.get(1).unwrap()), Err(Ok(Error::CommitmentMismatch)));
+    for (user, secret) in users.iter().zip(secrets.iter()) {
+        client.reveal_seed(&user, &circle_id, &secret);
+    }
+    for i in (0..3).rev() {
+        client.reveal_seed(&users.get(i).unwrap(), &mirror_id, &secrets.get(i).unwrap());
+    }
+
+    assert_eq!(client.get_circle(&circle_id).status, CircleStatus::Active);
+    let order = client.get_payout_order(&circle_id);
+    assert_eq!(order.len(), 3);
+    for user in users.iter() {
+        assert!(order.contains(&user));
+    }
+    // The same secrets revealed in the opposite order give the same payout order
+    assert_eq!(client.get_payout_order(&mirror_id), order);
+}
+
+#[test]
+fn members_who_never_reveal_forfeit_their_collateral() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let config = CircleConfig { order_mode: OrderMode::CommitReveal, collateral_amount: 40, ..rotation_config(2) };
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &no_penalty(), &config);
+    let users = join(&env, &client, &token, circle_id, 2);
+    let (honest, withholder) = (users.get(0).unwrap(), users.get(1).unwrap());
+
+    let secrets = [BytesN::from_array(&env, &[1; 32]), BytesN::from_array(&env, &[2; 32])];
+    for (user, secret) in users.iter().zip(secrets.iter()) {
+        client.commit_seed(&user, &circle_id, &env.crypto().sha256(&Bytes::from(secret.clone())));
+    }
+    client.reveal_seed(&honest, &circle_id, &secrets[0]);
+
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + 1);
+    client.cancel_circle(&circle_id);
+    assert_eq!(client.get_collateral(&circle_id, &withholder), 0);
+    assert_eq!(client.get_reserve(&circle_id, &token), 40);
+    client.claim(&honest, &circle_id);
+    assert_eq!(balance(&env, &token, &honest), 10_000);
+    assert_eq!(client.try_claim(&withholder, &circle_id), Err(Ok(Error::NoClaimableBalance)));
+}
+
+#[test]
+fn collateral_is_held_until_the_circle_completes() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let config = CircleConfig { collateral_amount: 50, ..rotation_config(2) };
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &no_penalty(), &config);
+    let users = join(&env, &client, &token, circle_id, 2);
+    let first = users.get(0).unwrap();
+    assert_eq!(balance(&env, &token, &first), 10_000 - 50);
+    assert_eq!(client.get_collateral(&circle_id, &first), 50);
+
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    client.distribute_funds(&admin, &circle_id);
+    client.claim(&first, &circle_id);
+    assert_eq!(client.get_collateral(&circle_id, &first), 50);
+
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    client.distribute_funds(&admin, &circle_id);
+    client.claim(&first, &circle_id);
+    client.claim(&users.get(1).unwrap(), &circle_id);
+    for user in users.iter() {
+        assert_eq!(balance(&env, &token, &user), 10_000);
+        assert_eq!(client.get_collateral(&circle_id, &user), 0);
+    }
+}
+
+#[test]
+fn defaults_are_covered_by_collateral_then_the_reserve() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let penalty = PenaltyPolicy { rate_bps: 0, flat_fee: 50, min_fee: 0, max_escalation_days: 0, grace_period: DAY };
+    let config = CircleConfig { collateral_amount: 60, ..rotation_config(3) };
+    let circle_id = client.create_circle(&admin, &100, &3, &token, &(7 * DAY), &penalty, &config);
+    let users = join(&env, &client, &token, circle_id, 3);
+    let (first, second, third) = (users.get(0).unwrap(), users.get(1).unwrap(), users.get(2).unwrap());
+
+    client.deposit(&first, &circle_id);
+    assert_eq!(client.try_mark_default(&circle_id, &second), Err(Ok(Error::DeadlineNotReached)));
+
+    // A late fee funds the reserve, which advances what collateral leaves uncovered as debt
+    env.ledger().with_mut(|l| l.timestamp = 8 * DAY + 1);
+    client.deposit(&third, &circle_id);
+    assert_eq!(client.get_reserve(&circle_id, &token), 50);
+    client.mark_default(&circle_id, &second);
+    assert_eq!(client.get_collateral(&circle_id, &second), 0);
+    assert_eq!(client.get_reserve(&circle_id, &token), 10);
+    assert_eq!(client.get_debt(&circle_id, &second), 40);
+
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &first), 300);
+
+    assert_eq!(client.try_repay_debt(&second, &circle_id, &41), Err(Ok(Error::InvalidAmount)));
+    client.repay_debt(&second, &circle_id, &40);
+    assert_eq!(client.get_debt(&circle_id, &second), 0);
+    assert_eq!(client.get_reserve(&circle_id, &token), 50);
+}
+
+#[test]
+fn uncovered_defaults_let_the_round_close_and_are_owed_to_its_recipient() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 3);
+    let users = join(&env, &client, &token, circle_id, 3);
+    let (first, absent, third) = (users.get(0).unwrap(), users.get(1).unwrap(), users.get(2).unwrap());
+
+    // No collateral and an empty reserve: the round still closes with what was paid
+    client.deposit(&first, &circle_id);
+    client.deposit(&third, &circle_id);
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + 1);
+    client.mark_default(&circle_id, &absent);
+    assert_eq!(client.get_round(&circle_id, &1).status, RoundStatus::Closed);
+    assert_eq!(client.get_debt(&circle_id, &absent), 100);
+    assert_eq!(client.get_reputation(&absent).open_defaults, 1);
+
+    client.distribute_funds(&admin, &circle_id);
+    assert_eq!(client.get_claimable(&circle_id, &first), 200);
+    assert_eq!(client.get_round(&circle_id, &2).status, RoundStatus::Open);
+
+    // Repayments go to the round's recipient, not the reserve
+    client.repay_debt(&absent, &circle_id, &60);
+    assert_eq!(client.get_claimable(&circle_id, &first), 260);
+    client.repay_debt(&absent, &circle_id, &40);
+    assert_eq!(client.get_claimable(&circle_id, &first), 300);
+    assert_eq!(client.get_reserve(&circle_id, &token), 0);
+    assert_eq!(client.get_debt(&circle_id, &absent), 0);
+    assert_eq!(client.get_reputation(&absent).open_defaults, 0);
+    client.claim(&first, &circle_id);
+    assert_eq!(balance(&env, &token, &first), 10_000 - 100 + 300);
+}
+
+#[test]
+fn reputation_follows_members_across_circles() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let grace = PenaltyPolicy { grace_period: DAY, ..no_penalty() };
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &grace, &rotation_config(2));
+    let users = join(&env, &client, &token, circle_id, 2);
+    let (prompt, late) = (users.get(0).unwrap(), users.get(1).unwrap());
+
+    // Paying within the grace period still counts as on time
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + DAY / 2);
+    client.deposit(&prompt, &circle_id);
+    env.ledger().with_mut(|l| l.timestamp = 9 * DAY);
+    client.deposit(&late, &circle_id);
+    client.distribute_funds(&admin, &circle_id);
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    client.distribute_funds(&admin, &circle_id);
+
+    let record = client.get_reputation(&late);
+    assert_eq!((record.rounds_on_time, record.rounds_late), (1, 1));
+    assert_eq!((record.circles_joined, record.circles_completed), (1, 1));
+    assert_eq!(client.get_reputation(&prompt).rounds_late, 0);
+
+    // An early contribution refunded on cancellation no longer counts as on time
+    let stale_id = create(&client, &admin, &token, 3);
+    client.join_circle(&prompt, &stale_id);
+    client.deposit(&prompt, &stale_id);
+    assert_eq!(client.get_reputation(&prompt).rounds_on_time, 3);
+    env.ledger().with_mut(|l| l.timestamp = 16 * DAY + 1);
+    client.cancel_circle(&stale_id);
+    let record = client.get_reputation(&prompt);
+    assert_eq!((record.rounds_on_time, record.circles_joined), (2, 2));
+}
+
+#[test]
+fn gated_circles_admit_only_members_with_a_track_record() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let open_id = create(&client, &admin, &token, 2);
+    let users = join(&env, &client, &token, open_id, 2);
+    for _ in 0..2 {
+        for user in users.iter() {
+            client.deposit(&user, &open_id);
+        }
+        client.distribute_funds(&admin, &open_id);
+    }
+
+    let criteria = AdmissionCriteria { min_completed_circles: 1, max_open_defaults: 0, max_late_ratio_bps: 1_000 };
+    let config = CircleConfig { admission: Admission::Gated(criteria), ..rotation_config(2) };
+    let gated_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &no_penalty(), &config);
+    let newcomer = Address::generate(&env);
+    assert_eq!(client.try_join_circle(&newcomer, &gated_id), Err(Ok(Error::AdmissionCriteriaNotMet)));
+    client.join_circle(&users.get(0).unwrap(), &gated_id);
+    assert_eq!(client.get_members(&gated_id).len(), 1);
+}
+
+#[test]
+fn creator_appoints_co_organizers_who_can_run_the_circle() {
+    let env = Env::default();
+    let (client, _, token) = setup(&env);
+    let creator = Address::generate(&env);
+    let helper = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let circle_id = create(&client, &creator, &token, 2);
+
+    assert_eq!(client.try_add_co_organizer(&stranger, &circle_id, &helper), Err(Ok(Error::Unauthorized)));
+    client.add_co_organizer(&creator, &circle_id, &helper);
+    assert_eq!(client.try_add_co_organizer(&creator, &circle_id, &helper), Err(Ok(Error::AlreadyOrganizer)));
+    assert_eq!(client.try_add_co_organizer(&creator, &circle_id, &creator), Err(Ok(Error::AlreadyOrganizer)));
+    assert_eq!(client.try_remove_co_organizer(&creator, &circle_id, &stranger), Err(Ok(Error::NotOrganizer)));
+    for _ in 0..4 {
+        client.add_co_organizer(&creator, &circle_id, &Address::generate(&env));
+    }
+    assert_eq!(client.try_add_co_organizer(&creator, &circle_id, &stranger), Err(Ok(Error::TooManyOrganizers)));
+
+    let users = join(&env, &client, &token, circle_id, 2);
+    for user in users.iter() {
+        client.deposit(&user, &circle_id);
+    }
+    assert_eq!(client.try_distribute_funds(&stranger, &circle_id), Err(Ok(Error::Unauthorized)));
+    client.distribute_funds(&helper, &circle_id);
+
+    client.remove_co_organizer(&creator, &circle_id, &helper);
+    assert!(!client.get_co_organizers(&circle_id).contains(&helper));
+    assert_eq!(client.try_set_next_cycle_amount(&helper, &circle_id, &200), Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn admin_transfer_needs_acceptance_within_the_window() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+    let successor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(client.try_cancel_admin_transfer(&admin), Err(Ok(Error::NoPendingAdmin)));
+    client.transfer_admin(&admin, &successor);
+    assert_eq!(client.try_accept_admin(&stranger), Err(Ok(Error::Unauthorized)));
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + 1);
+    assert_eq!(client.try_accept_admin(&successor), Err(Ok(Error::AdminTransferExpired)));
+
+    client.transfer_admin(&admin, &stranger);
+    client.cancel_admin_transfer(&admin);
+    assert!(matches!(client.try_get_pending_admin(), Err(Ok(Error::NoPendingAdmin))));
+
+    client.transfer_admin(&admin, &successor);
+    assert_eq!(client.get_pending_admin().new_admin, successor);
+    client.accept_admin(&successor);
+    assert_eq!(client.try_transfer_admin(&admin, &stranger), Err(Ok(Error::Unauthorized)));
+    assert!(client.has_role(&Role::SuperAdmin, &successor));
+    assert!(!client.has_role(&Role::SuperAdmin, &admin));
+}
+
+#[test]
+fn init_runs_once_and_only_for_the_deployer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let deployer = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    // A contract the caller did not deploy with this salt cannot be claimed through init
+    let other_id = env.register_contract(None, SoroSusu);
+    let other = SoroSusuClient::new(&env, &other_id);
+    assert_eq!(other.try_circle_count(), Err(Ok(Error::NotInitialized)));
+    assert_eq!(other.try_init(&deployer, &salt), Err(Ok(Error::Unauthorized)));
+
+    let (client, admin, _) = setup(&env);
+    let first_salt = BytesN::from_array(&env, &[1; 32]);
+    assert_eq!(client.try_init(&admin, &first_salt), Err(Ok(Error::AlreadyInitialized)));
+    assert_eq!(client.try_init(&deployer, &salt), Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.circle_count(), 0);
+}
+
+#[test]
+fn protocol_actions_need_k_of_n_super_admin_approvals() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+    let second = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    // With a 1-of-1 threshold the admin's own proposal executes straight away
+    client.propose_action(&admin, &ProtocolAction::GrantRole(Role::SuperAdmin, second.clone()));
+    client.propose_action(&admin, &ProtocolAction::SetApprovalThreshold(2));
+    assert_eq!(client.get_approval_threshold(), 2);
+
+    let grant = ProtocolAction::GrantRole(Role::Pauser, pauser.clone());
+    assert_eq!(client.try_propose_action(&stranger, &grant), Err(Ok(Error::Unauthorized)));
+    let proposal_id = client.propose_action(&admin, &grant);
+    assert!(!client.get_proposal(&proposal_id).executed);
+    assert_eq!(client.try_approve_action(&admin, &proposal_id), Err(Ok(Error::AlreadyApproved)));
+    assert_eq!(client.try_approve_action(&stranger, &proposal_id), Err(Ok(Error::Unauthorized)));
+
+    client.approve_action(&second, &proposal_id);
+    assert!(client.get_proposal(&proposal_id).executed);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    assert_eq!(client.try_approve_action(&second, &proposal_id), Err(Ok(Error::ProposalExecuted)));
+    assert_eq!(client.try_propose_action(&admin, &grant), Err(Ok(Error::RoleAlreadyHeld)));
+    assert_eq!(client.try_propose_action(&admin, &ProtocolAction::SetApprovalThreshold(3)), Err(Ok(Error::InvalidThreshold)));
+}
+
+#[test]
+fn approvals_from_revoked_super_admins_do_not_count() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+    let seats = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    for seat in seats.iter() {
+        client.propose_action(&admin, &ProtocolAction::GrantRole(Role::SuperAdmin, seat.clone()));
+    }
+    client.propose_action(&admin, &ProtocolAction::SetApprovalThreshold(3));
+
+    let pauser = Address::generate(&env);
+    let grant_id = client.propose_action(&admin, &ProtocolAction::GrantRole(Role::Pauser, pauser.clone()));
+    client.approve_action(&seats[0], &grant_id);
+
+    let revoke_id = client.propose_action(&admin, &ProtocolAction::RevokeRole(Role::SuperAdmin, seats[0].clone()));
+    client.approve_action(&seats[1], &revoke_id);
+    client.approve_action(&seats[2], &revoke_id);
+    assert!(!client.has_role(&Role::SuperAdmin, &seats[0]));
+
+    // Two of the three recorded approvals are still valid, one short of the threshold
+    client.approve_action(&seats[1], &grant_id);
+    assert!(!client.get_proposal(&grant_id).executed);
+    client.approve_action(&seats[2], &grant_id);
+    assert!(client.get_proposal(&grant_id).executed);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+}
+
+#[test]
+fn circle_pause_is_for_pausers_and_freezes_deadlines() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let pauser = Address::generate(&env);
+    client.propose_action(&admin, &ProtocolAction::GrantRole(Role::Pauser, pauser.clone()));
+    let creator = Address::generate(&env);
+    let circle_id = create(&client, &creator, &token, 3);
+    let users = join(&env, &client, &token, circle_id, 1);
+    let member = users.get(0).unwrap();
+
+    assert_eq!(client.try_pause_circle(&creator, &circle_id), Err(Ok(Error::Unauthorized)));
+    env.ledger().with_mut(|l| l.timestamp = DAY);
+    client.pause_circle(&pauser, &circle_id);
+    assert!(client.is_paused(&circle_id));
+    assert_eq!(client.try_deposit(&member, &circle_id), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_start_circle(&creator, &circle_id), Err(Ok(Error::Paused)));
+
+    // The fill deadline (day 7) passes while paused, but cancelling is blocked and the
+    // deadline moves back by the paused week
+    env.ledger().with_mut(|l| l.timestamp = 8 * DAY);
+    assert_eq!(client.try_cancel_circle(&circle_id), Err(Ok(Error::Paused)));
+    client.unpause_circle(&pauser, &circle_id);
+    assert_eq!(client.try_cancel_circle(&circle_id), Err(Ok(Error::DeadlineNotReached)));
+    join(&env, &client, &token, circle_id, 2);
+    assert_eq!(client.get_circle(&circle_id).status, CircleStatus::Active);
+
+    // A protocol-wide pause blocks every circle
+    client.pause(&pauser);
+    assert_eq!(client.try_deposit(&member, &circle_id), Err(Ok(Error::Paused)));
+    client.unpause(&pauser);
+    client.deposit(&member, &circle_id);
+}
+
+#[test]
+fn legacy_storage_is_migrated_to_the_current_schema() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let token = env.register_stellar_asset_contract(admin.clone());
+    let users = [Address::generate(&env), Address::generate(&env)];
+    let contract_id = env.register_contract(None, SoroSusu);
+    let client = SoroSusuClient::new(&env, &contract_id);
+
+    // Lay out storage the way the original contract did
+    let legacy_circle = |max_members: Val| {
+        let mut record: Map<Symbol, Val> = Map::new(&env);
+        record.set(Symbol::new(&env, "contribution_amount"), 100u64.into_val(&env));
+        record.set(Symbol::new(&env, "max_members"), max_members);
+        record.set(Symbol::new(&env, "cycle_duration"), 0u64.into_val(&env));
+        record.set(Symbol::new(&env, "creator"), admin.into_val(&env));
+        record.set(Symbol::new(&env, "token"), token.into_val(&env));
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(1), &record));
+    };
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::CircleCount, &1u64);
+        for user in users.iter() {
+            let legacy = LegacyMember {
+                address: user.clone(),
+                has_contributed: true,
+                contribution_count: 1,
+                last_contribution_time: 0,
+            };
+            storage.set(&LegacyDataKey::Member(user.clone()), &legacy);
+        }
+        storage.set(&LegacyDataKey::Deposit(1, users[0].clone()), &true);
+        storage.set(&DataKey::ClaimableBalance(1, users[1].clone()), &500i128);
+    });
+    // The legacy records claim 600 but the contract only holds 150
+    fund(&env, &token, &contract_id, 150);
+
+    // An undecodable record is reported rather than mistaken for a missing one
+    legacy_circle(Symbol::new(&env, "two").into_val(&env));
+    assert_eq!(client.try_migrate(&admin, &10), Err(Ok(Error::InvalidLegacyData)));
+    legacy_circle(1u32.into_val(&env));
+
+    assert_eq!(client.try_circle_count(), Err(Ok(Error::NotInitialized)));
+    assert_eq!(client.migrate(&admin, &10), 1);
+    assert_eq!(client.get_schema_version(), 2);
+    // Out-of-range legacy settings are clamped to what create_circle accepts
+    let circle = client.get_circle(&1);
+    assert_eq!(circle.status, CircleStatus::Forming);
+    assert_eq!((circle.max_members, circle.cycle_duration), (2, DAY));
+
+    let first = Vec::from_array(&env, [users[0].clone()]);
+    assert_eq!(client.migrate_members(&admin, &1, &first), 1);
+    assert_eq!(client.try_migrate_members(&admin, &1, &first), Err(Ok(Error::NotMember)));
+    assert_eq!(client.get_pot(&1, &1), 100);
+    assert_eq!(client.get_reputation(&users[0]).rounds_on_time, 1);
+    client.migrate_members(&admin, &1, &Vec::from_array(&env, [users[1].clone()]));
+    assert_eq!(client.get_claimable(&1, &users[1]), 50);
+    assert_eq!(client.get_circle(&1).status, CircleStatus::Active);
+}
+
+#[test]
+fn circle_data_lives_in_persistent_storage() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 3);
+    let users = join(&env, &client, &token, circle_id, 1);
+    let member = users.get(0).unwrap();
+
+    env.as_contract(&client.address, || {
+        let circle_key = DataKey::Circle(circle_id);
+        let member_key = DataKey::Member(circle_id, member.clone());
+        assert!(env.storage().persistent().has(&circle_key));
+        assert!(env.storage().persistent().has(&member_key));
+        assert!(!env.storage().instance().has(&circle_key));
+        assert!(!env.storage().instance().has(&member_key));
+    });
+
+    // Entries outlive the default TTL because every access extends them; bump_circle and pages
+    // of bump_members keep an idle circle alive
+    let circle_id = create(&client, &admin, &token, 5);
+    let members = join(&env, &client, &token, circle_id, 4);
+    env.ledger().with_mut(|l| l.sequence_number += 100 * 17_280);
+    client.bump_circle(&circle_id);
+    assert_eq!(client.bump_members(&circle_id, &0, &100), 3);
+    assert_eq!(client.bump_members(&circle_id, &3, &100), 1);
+    assert_eq!(client.bump_members(&circle_id, &4, &100), 0);
+    env.ledger().with_mut(|l| l.sequence_number += 100 * 17_280);
+    assert_eq!(client.get_members(&circle_id), members);
+    for member in members.iter() {
+        assert!(!client.get_member_status(&circle_id, &member).paid_current_round);
+    }
+    assert_eq!(client.try_bump_circle(&99), Err(Ok(Error::CircleNotFound)));
+}
+
+#[test]
+fn state_changes_emit_versioned_events() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let circle_id = create(&client, &admin, &token, 3);
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(topics, (Symbol::new(&env, "circle_created"), EVENT_VERSION, circle_id).into_val(&env));
+
+    let users = join(&env, &client, &token, circle_id, 1);
+    let member = users.get(0).unwrap();
+    client.deposit(&member, &circle_id);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (Symbol::new(&env, "deposit"), EVENT_VERSION, circle_id).into_val(&env));
+    assert_eq!(DepositEvent::try_from_val(&env, &data).unwrap().amount, 100);
+
+    client.leave_circle(&member, &circle_id);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (Symbol::new(&env, "member_left"), EVENT_VERSION, circle_id).into_val(&env));
+    let event = MemberLeftEvent::try_from_val(&env, &data).unwrap();
+    assert_eq!((event.member_count, event.refund), (0, 100));
+    assert_eq!(balance(&env, &token, &member), 10_000);
+
+    client.transfer_admin(&admin, &member);
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(topics, (Symbol::new(&env, "admin_proposed"), EVENT_VERSION).into_val(&env));
+}
+
+#[test]
+fn queries_report_circle_and_member_state() {
+    let env = Env::default();
+    let (client, admin, token) = setup(&env);
+    let penalty = PenaltyPolicy { rate_bps: 0, flat_fee: 10, min_fee: 0, max_escalation_days: 0, grace_period: 0 };
+    let config = CircleConfig { collateral_amount: 25, ..rotation_config(2) };
+    let circle_id = client.create_circle(&admin, &100, &2, &token, &(7 * DAY), &penalty, &config);
+    assert_eq!(client.circle_count(), 1);
+    assert!(matches!(client.try_get_member_status(&circle_id, &admin), Err(Ok(Error::NotMember))));
+
+    let users = join(&env, &client, &token, circle_id, 2);
+    let (first, second) = (users.get(0).unwrap(), users.get(1).unwrap());
+    client.deposit(&first, &circle_id);
+    env.ledger().with_mut(|l| l.timestamp = 7 * DAY + 1);
+
+    let status = client.get_member_status(&circle_id, &second);
+    assert_eq!((status.round, status.paid_current_round), (1, false));
+    assert_eq!((status.penalty_due, status.amount_due), (10, 110));
+    assert_eq!(status.collateral, 25);
+    let status = client.get_member_status(&circle_id, &first);
+    assert_eq!((status.paid_current_round, status.amount_due), (true, 0));
+
+    let circle = client.get_circle(&circle_id);
+    assert_eq!((circle.member_count, circle.current_round), (2, 1));
+    assert_eq!(client.get_payout_order(&circle_id), users);
+    assert_eq!(client.get_round(&circle_id, &1).paid_count, 1);
+    assert!(client.get_best_bid(&circle_id, &1).is_none());
+    assert!(matches!(client.try_get_best_bid(&99, &1), Err(Ok(Error::CircleNotFound))));
+    assert!(!client.is_paused(&circle_id));
+}